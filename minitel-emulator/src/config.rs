@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use bevy::window::WindowResolution;
 
 pub const SCREEN_COLUMNS: u8 = 40;
+pub const MAX_SCREEN_COLUMNS: u8 = 80;
 pub const SCREEN_ROWS: u8 = 24;
 pub const GUIDE_PANEL_WIDTH: f32 = 200.0;
 
@@ -42,4 +43,10 @@ impl EmulatorConfig {
             SCREEN_ROWS as f32 * self.cell_size.y,
         )
     }
+
+    /// Size of a cell when the screen displays the given amount of columns,
+    /// the screen keeps its size so cells get narrower in 80 columns mode
+    pub fn cell_size_for(&self, columns: u8) -> Vec2 {
+        Vec2::new(self.screen_size().x / columns as f32, self.cell_size.y)
+    }
 }
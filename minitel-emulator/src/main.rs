@@ -5,14 +5,20 @@ mod transport;
 
 use crate::config::EmulatorConfig;
 use crate::render::{EmulatorPlugin, TransportResource};
-use crate::transport::{TcpTransport, EMULATOR_PORT};
+use crate::transport::{TcpTransport, Transport, EMULATOR_PORT};
 use bevy::prelude::*;
 use bevy::window::WindowPlugin;
 
 fn main() {
     let config = EmulatorConfig::default();
-    let transport = TcpTransport::bind().expect("failed to bind TCP transport");
-    println!("Minitel emulator listening on 127.0.0.1:{EMULATOR_PORT}");
+    let transport = if std::env::args().any(|arg| arg == "--pty") {
+        open_pty()
+    } else {
+        let transport = TcpTransport::bind().expect("failed to bind TCP transport");
+        println!("Minitel emulator listening on 127.0.0.1:{EMULATOR_PORT}");
+
+        Transport::Tcp(transport)
+    };
 
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
@@ -33,3 +39,17 @@ fn main() {
         .add_plugins(EmulatorPlugin)
         .run();
 }
+
+#[cfg(target_os = "linux")]
+fn open_pty() -> Transport {
+    let transport = crate::transport::PtyTransport::open().expect("failed to open PTY transport");
+    println!("Minitel emulator available on {}", transport.path().display());
+
+    Transport::Pty(transport)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_pty() -> Transport {
+    eprintln!("--pty is only available on Linux");
+    std::process::exit(1);
+}
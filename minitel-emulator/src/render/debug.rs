@@ -1,4 +1,4 @@
-use crate::config::{EmulatorConfig, SCREEN_COLUMNS, SCREEN_ROWS};
+use crate::config::{EmulatorConfig, SCREEN_ROWS};
 use bevy::prelude::*;
 
 use super::palette::cell_position;
@@ -93,6 +93,12 @@ pub(super) struct DebugCursorCol;
 #[derive(Component)]
 pub(super) struct DebugCursorRow;
 
+#[derive(Component)]
+pub(super) struct DebugWarningCount;
+
+#[derive(Component)]
+pub(super) struct DebugLastWarning;
+
 // --- Systems ---
 
 pub(super) fn handle_debug_shortcuts(
@@ -141,15 +147,19 @@ pub(super) fn update_debug_overlay(
     config: Res<EmulatorConfig>,
     terminal: Res<TerminalState>,
     debug_state: Res<DebugState>,
-    mut highlight: Query<(&mut Transform, &mut Visibility), (With<DebugHighlight>, Without<DebugCursorHighlight>, Without<GridOverlay>)>,
-    mut cursor_hl: Query<(&mut Transform, &mut Visibility), (With<DebugCursorHighlight>, Without<DebugHighlight>, Without<GridOverlay>)>,
+    mut highlight: Query<(&mut Transform, &mut Visibility, &mut Sprite), (With<DebugHighlight>, Without<DebugCursorHighlight>, Without<GridOverlay>)>,
+    mut cursor_hl: Query<(&mut Transform, &mut Visibility, &mut Sprite), (With<DebugCursorHighlight>, Without<DebugHighlight>, Without<GridOverlay>)>,
     mut grid_lines: Query<&mut Visibility, (With<GridOverlay>, Without<DebugHighlight>, Without<DebugCursorHighlight>)>,
     mut mouse_col: Query<&mut Text2d, (With<DebugMouseCol>, Without<DebugMouseRow>, Without<DebugCursorCol>, Without<DebugCursorRow>)>,
     mut mouse_row: Query<&mut Text2d, (With<DebugMouseRow>, Without<DebugMouseCol>, Without<DebugCursorCol>, Without<DebugCursorRow>)>,
     mut cursor_col: Query<&mut Text2d, (With<DebugCursorCol>, Without<DebugCursorRow>, Without<DebugMouseCol>, Without<DebugMouseRow>)>,
     mut cursor_row: Query<&mut Text2d, (With<DebugCursorRow>, Without<DebugCursorCol>, Without<DebugMouseCol>, Without<DebugMouseRow>)>,
+    mut warning_count: Query<&mut Text2d, (With<DebugWarningCount>, Without<DebugLastWarning>, Without<DebugMouseCol>, Without<DebugMouseRow>, Without<DebugCursorCol>, Without<DebugCursorRow>)>,
+    mut last_warning: Query<&mut Text2d, (With<DebugLastWarning>, Without<DebugWarningCount>, Without<DebugMouseCol>, Without<DebugMouseRow>, Without<DebugCursorCol>, Without<DebugCursorRow>)>,
 ) {
     let ctx = terminal.parser.ctx();
+    let columns = ctx.screen_width;
+    let cell_size = config.cell_size_for(columns);
 
     // cursor position text
     if let Ok(mut text) = cursor_col.single_mut() {
@@ -159,6 +169,14 @@ pub(super) fn update_debug_overlay(
         **text = format!("{}", ctx.cursor_y);
     }
 
+    // parser warnings
+    if let Ok(mut text) = warning_count.single_mut() {
+        **text = format!("{}", terminal.warning_count);
+    }
+    if let (Ok(mut text), Some(warning)) = (last_warning.single_mut(), &terminal.last_warning) {
+        **text = format!("{:?} @{}", warning.kind(), warning.offset().unwrap_or_default());
+    }
+
     // grid overlay
     let grid_vis = if debug_state.grid_visible { Visibility::Visible } else { Visibility::Hidden };
     for mut vis in &mut grid_lines {
@@ -167,20 +185,21 @@ pub(super) fn update_debug_overlay(
 
     // cursor highlight
     if debug_state.cursor_highlight {
-        let origin = cell_position(&config, ctx.cursor_x, ctx.cursor_y);
-        if let Ok((mut transform, mut vis)) = cursor_hl.single_mut() {
+        let origin = cell_position(&config, columns, ctx.cursor_x, ctx.cursor_y);
+        if let Ok((mut transform, mut vis, mut sprite)) = cursor_hl.single_mut() {
             transform.translation = origin.extend(super::DEBUG_HIGHLIGHT_Z);
+            sprite.custom_size = Some(cell_size);
             *vis = Visibility::Visible;
         }
     } else {
-        if let Ok((_, mut vis)) = cursor_hl.single_mut() {
+        if let Ok((_, mut vis, _)) = cursor_hl.single_mut() {
             *vis = Visibility::Hidden;
         }
     }
 
     // mouse highlight
     if !debug_state.mouse_highlight {
-        if let Ok((_, mut vis)) = highlight.single_mut() {
+        if let Ok((_, mut vis, _)) = highlight.single_mut() {
             *vis = Visibility::Hidden;
         }
         if let Ok(mut text) = mouse_col.single_mut() {
@@ -196,7 +215,7 @@ pub(super) fn update_debug_overlay(
         (window.single(), camera.single()) else { return };
 
     let Some(cursor_pos) = window.cursor_position() else {
-        if let Ok((_, mut vis)) = highlight.single_mut() {
+        if let Ok((_, mut vis, _)) = highlight.single_mut() {
             *vis = Visibility::Hidden;
         }
         if let Ok(mut text) = mouse_col.single_mut() {
@@ -216,14 +235,15 @@ pub(super) fn update_debug_overlay(
     let left = -screen.x / 2.0;
     let top_edge = screen.y / 2.0;
 
-    let col = ((world.x - left) / config.cell_size.x).floor() as i32 + 1;
-    let row = ((top_edge - world.y) / config.cell_size.y).floor() as i32 + 1;
+    let col = ((world.x - left) / cell_size.x).floor() as i32 + 1;
+    let row = ((top_edge - world.y) / cell_size.y).floor() as i32 + 1;
 
-    if col >= 1 && col <= SCREEN_COLUMNS as i32 && row >= 1 && row <= SCREEN_ROWS as i32 {
-        let origin = cell_position(&config, col as u8, row as u8);
+    if col >= 1 && col <= columns as i32 && row >= 1 && row <= SCREEN_ROWS as i32 {
+        let origin = cell_position(&config, columns, col as u8, row as u8);
 
-        if let Ok((mut transform, mut vis)) = highlight.single_mut() {
+        if let Ok((mut transform, mut vis, mut sprite)) = highlight.single_mut() {
             transform.translation = origin.extend(super::DEBUG_HIGHLIGHT_Z);
+            sprite.custom_size = Some(cell_size);
             *vis = Visibility::Visible;
         }
 
@@ -234,7 +254,7 @@ pub(super) fn update_debug_overlay(
             **text = format!("{row}");
         }
     } else {
-        if let Ok((_, mut vis)) = highlight.single_mut() {
+        if let Ok((_, mut vis, _)) = highlight.single_mut() {
             *vis = Visibility::Hidden;
         }
         if let Ok(mut text) = mouse_col.single_mut() {
@@ -4,10 +4,12 @@ mod palette;
 mod setup;
 mod terminal;
 
-use crate::transport::TcpTransport;
+use crate::transport::Transport;
 use bevy::prelude::*;
 use std::collections::VecDeque;
-use teletel_protocol::parser::{DisplayComponent, Parser};
+use teletel_protocol::parser::{
+    Error as ParseError, Identification, Model, Parser, Policy, TerminalProfile,
+};
 
 const BLINK_INTERVAL_SECS: f32 = 0.5;
 const CURSOR_Z: f32 = 3.0;
@@ -41,7 +43,7 @@ impl Plugin for EmulatorPlugin {
 // --- Shared resources and components ---
 
 #[derive(Resource, Deref, DerefMut)]
-pub struct TransportResource(pub TcpTransport);
+pub struct TransportResource(pub Transport);
 
 #[derive(Resource)]
 pub struct TerminalState {
@@ -50,16 +52,27 @@ pub struct TerminalState {
     blink_timer: Timer,
     pub(crate) pending_bytes: VecDeque<u8>,
     pub(crate) byte_budget: f32,
+    pub(crate) warning_count: usize,
+    pub(crate) last_warning: Option<ParseError>,
 }
 
 impl Default for TerminalState {
     fn default() -> Self {
+        let mut parser = Parser::from_profile(TerminalProfile::MINITEL_2).with_policy(Policy::Collect);
+        //answers the ROM identification request as an emulator
+        parser.set_identification(Identification {
+            model: Model::Emulator,
+            ..Identification::default()
+        });
+
         Self {
-            parser: Parser::new(DisplayComponent::VGP5),
+            parser,
             blink_visible: true,
             blink_timer: Timer::from_seconds(BLINK_INTERVAL_SECS, TimerMode::Repeating),
             pending_bytes: VecDeque::new(),
             byte_budget: 0.0,
+            warning_count: 0,
+            last_warning: None,
         }
     }
 }
@@ -1,19 +1,20 @@
-use crate::config::{EmulatorConfig, SCREEN_COLUMNS};
+use crate::config::{EmulatorConfig, MAX_SCREEN_COLUMNS};
 use bevy::prelude::*;
 use teletel_protocol::parser::Cell;
 
 pub(super) fn cell_index(x: u8, y: u8) -> usize {
-    ((y - 1) as usize * SCREEN_COLUMNS as usize) + (x - 1) as usize
+    ((y - 1) as usize * MAX_SCREEN_COLUMNS as usize) + (x - 1) as usize
 }
 
-pub(super) fn cell_position(config: &EmulatorConfig, x: u8, y: u8) -> Vec2 {
+pub(super) fn cell_position(config: &EmulatorConfig, columns: u8, x: u8, y: u8) -> Vec2 {
     let screen = config.screen_size();
+    let cell_size = config.cell_size_for(columns);
     let left = -screen.x / 2.0;
     let top = screen.y / 2.0;
 
     Vec2::new(
-        left + (x - 1) as f32 * config.cell_size.x,
-        top - y as f32 * config.cell_size.y,
+        left + (x - 1) as f32 * cell_size.x,
+        top - y as f32 * cell_size.y,
     )
 }
 
@@ -1,4 +1,4 @@
-use crate::config::{EmulatorConfig, GUIDE_PANEL_WIDTH, SCREEN_COLUMNS, SCREEN_ROWS};
+use crate::config::{EmulatorConfig, GUIDE_PANEL_WIDTH, MAX_SCREEN_COLUMNS, SCREEN_ROWS};
 use bevy::camera::{OrthographicProjection, Projection, ScalingMode};
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
@@ -25,11 +25,12 @@ pub(super) fn setup_terminal(mut commands: Commands, config: Res<EmulatorConfig>
         Transform::from_translation(Vec3::new(GUIDE_PANEL_WIDTH / 2.0, 0.0, 0.0)),
     ));
 
-    // cell entities (background + foreground per cell)
-    let mut cells = Vec::with_capacity((SCREEN_COLUMNS as usize) * (SCREEN_ROWS as usize));
+    // cell entities (background + foreground per cell), enough for the
+    // 80 columns mixte mode, cells outside of the current width are hidden
+    let mut cells = Vec::with_capacity((MAX_SCREEN_COLUMNS as usize) * (SCREEN_ROWS as usize));
     for y in 1..=SCREEN_ROWS {
-        for x in 1..=SCREEN_COLUMNS {
-            let position = cell_position(&config, x, y);
+        for x in 1..=MAX_SCREEN_COLUMNS {
+            let position = cell_position(&config, MAX_SCREEN_COLUMNS, x, y);
             let background = commands
                 .spawn((
                     Sprite::from_color(Color::BLACK, config.cell_size),
@@ -232,7 +233,7 @@ pub(super) fn setup_terminal(mut commands: Commands, config: Res<EmulatorConfig>
     let col2_x = table_x + 55.0;
     let col3_x = table_x + 95.0;
 
-    let ty = table_bottom + row_height * 3.0 + 6.0;
+    let ty = table_bottom + row_height * 4.0 + 6.0;
     commands.spawn((
         Text2d::new("Information"),
         title_style,
@@ -240,6 +241,29 @@ pub(super) fn setup_terminal(mut commands: Commands, config: Res<EmulatorConfig>
         Transform::from_translation(Vec3::new(table_x, ty, FOREGROUND_Z)),
     ));
 
+    // parser warnings row
+    let ty = table_bottom + row_height * 3.0;
+    commands.spawn((
+        Text2d::new("Errors"),
+        key_style.clone(),
+        Anchor::BOTTOM_LEFT,
+        Transform::from_translation(Vec3::new(col1_x, ty, FOREGROUND_Z)),
+    ));
+    commands.spawn((
+        Text2d::new("0"),
+        key_style.clone(),
+        Anchor::BOTTOM_LEFT,
+        Transform::from_translation(Vec3::new(col2_x, ty, FOREGROUND_Z)),
+        DebugWarningCount,
+    ));
+    commands.spawn((
+        Text2d::new(""),
+        key_style.clone(),
+        Anchor::BOTTOM_LEFT,
+        Transform::from_translation(Vec3::new(col3_x, ty, FOREGROUND_Z)),
+        DebugLastWarning,
+    ));
+
     // mouse row
     let ty = table_bottom + row_height * 2.0;
     commands.spawn((
@@ -1,8 +1,8 @@
-use crate::config::{EmulatorConfig, SCREEN_COLUMNS, SCREEN_ROWS};
+use crate::config::{EmulatorConfig, MAX_SCREEN_COLUMNS, SCREEN_ROWS};
 use crate::glyphs::GlyphCache;
 use bevy::input::keyboard::KeyboardInput;
 use bevy::prelude::*;
-use teletel_protocol::parser::Context;
+use teletel_protocol::parser::{Context, Parser};
 
 use super::beep::BeepSound;
 use super::debug::DebugState;
@@ -54,6 +54,13 @@ pub(super) fn pump_transport_input(
         terminal.byte_budget = 0.0;
     }
 
+    // keep the recovered parser errors for the debug overlay
+    for warning in terminal.parser.take_diagnostics() {
+        warn!("{warning} at offset {:?}", warning.offset());
+        terminal.warning_count += 1;
+        terminal.last_warning = Some(warning);
+    }
+
     // send protocol response bytes back to the remote host
     let response = terminal.parser.take_response();
     if !response.is_empty() {
@@ -71,6 +78,7 @@ pub(super) fn capture_keyboard_input(
     keys: Res<ButtonInput<KeyCode>>,
     mut keyboard_inputs: MessageReader<KeyboardInput>,
     mut transport: ResMut<TransportResource>,
+    mut terminal: ResMut<TerminalState>,
 ) {
     // when ctrl is held, debug shortcuts are active, don't send keys to terminal
     let ctrl_held = keys.pressed(KeyCode::ControlLeft) || keys.pressed(KeyCode::ControlRight);
@@ -87,11 +95,15 @@ pub(super) fn capture_keyboard_input(
 
         if let Some(mapped) = map_named_key(event.key_code) {
             bytes.extend_from_slice(mapped);
+            // space is the only named key displayed on the screen
+            if event.key_code == KeyCode::Space {
+                echo(&mut terminal.parser, mapped);
+            }
             continue;
         }
 
         if let Some(text) = &event.text {
-            bytes.extend(text.chars().filter_map(map_character));
+            bytes.extend(type_text(&mut terminal.parser, text));
         }
     }
 
@@ -120,17 +132,18 @@ pub(super) fn render_terminal(
     mut visibility: Query<&mut Visibility>,
 ) {
     let ctx = terminal.parser.ctx();
+    let columns = ctx.screen_width;
+    let cell_size = config.cell_size_for(columns);
     let coverage = coverage_map(ctx);
 
     for y in 1..=SCREEN_ROWS {
-        for x in 1..=SCREEN_COLUMNS {
+        for x in 1..=MAX_SCREEN_COLUMNS {
             let index = cell_index(x, y);
-            let cell = ctx.grid.cell(x, y);
             let owner = coverage[index];
             let background_entity = entities.cells[index].background;
             let foreground_entity = entities.cells[index].foreground;
 
-            let Some(owner_index) = owner else {
+            let (Some(owner_index), Some(cell)) = (owner, ctx.grid.cell_opt(x, y)) else {
                 if let Ok(mut visible) = visibility.get_mut(background_entity) {
                     *visible = Visibility::Hidden;
                 }
@@ -154,13 +167,13 @@ pub(super) fn render_terminal(
             let (fg, bg) = palette_for(owner_cell, config.colors);
             let width = if owner_cell.attributes.double_width { 2.0 } else { 1.0 };
             let height = if owner_cell.attributes.double_height { 2.0 } else { 1.0 };
-            let position = cell_position(&config, x, y);
+            let position = cell_position(&config, columns, x, y);
 
             if let Ok(mut sprite) = sprites.get_mut(background_entity) {
                 sprite.color = bg;
                 sprite.custom_size = Some(Vec2::new(
-                    config.cell_size.x * width,
-                    config.cell_size.y * height,
+                    cell_size.x * width,
+                    cell_size.y * height,
                 ));
             }
             if let Ok(mut transform) = transforms.get_mut(background_entity) {
@@ -192,8 +205,8 @@ pub(super) fn render_terminal(
                     sprite.image = handle;
                     sprite.color = fg;
                     sprite.custom_size = Some(Vec2::new(
-                        config.cell_size.x * width,
-                        config.cell_size.y * height,
+                        cell_size.x * width,
+                        cell_size.y * height,
                     ));
                 }
                 if let Ok(mut transform) = transforms.get_mut(foreground_entity) {
@@ -215,21 +228,22 @@ pub(super) fn render_terminal(
 
     if cursor_visible {
         if let Ok(mut sprite) = sprites.get_mut(cursor_entity) {
-            sprite.custom_size = Some(config.cell_size);
+            sprite.custom_size = Some(cell_size);
             sprite.color = Color::srgba(1.0, 1.0, 1.0, 0.35);
         }
         if let Ok(mut transform) = transforms.get_mut(cursor_entity) {
             transform.translation =
-                cell_position(&config, ctx.cursor_x, ctx.cursor_y).extend(CURSOR_Z);
+                cell_position(&config, columns, ctx.cursor_x, ctx.cursor_y).extend(CURSOR_Z);
         }
     }
 }
 
 fn coverage_map(ctx: &Context) -> Vec<Option<usize>> {
-    let mut coverage = vec![None; (SCREEN_COLUMNS as usize) * (SCREEN_ROWS as usize)];
+    let columns = ctx.screen_width;
+    let mut coverage = vec![None; (MAX_SCREEN_COLUMNS as usize) * (SCREEN_ROWS as usize)];
 
     for y in 1..=SCREEN_ROWS {
-        for x in 1..=SCREEN_COLUMNS {
+        for x in 1..=columns {
             let index = cell_index(x, y);
             let cell = ctx.grid.cell(x, y);
 
@@ -242,7 +256,7 @@ fn coverage_map(ctx: &Context) -> Vec<Option<usize>> {
                     let target_y = y.saturating_sub(dy);
 
                     if target_x == 0
-                        || target_x > SCREEN_COLUMNS
+                        || target_x > columns
                         || target_y == 0
                         || target_y > SCREEN_ROWS
                     {
@@ -258,6 +272,27 @@ fn coverage_map(ctx: &Context) -> Vec<Option<usize>> {
     coverage
 }
 
+/// Bytes sent for the typed text, also displayed when the keyboard is
+/// routed to the screen
+fn type_text(parser: &mut Parser, text: &str) -> Vec<u8> {
+    let bytes: Vec<u8> = text.chars().filter_map(map_character).collect();
+    echo(parser, &bytes);
+
+    bytes
+}
+
+fn echo(parser: &mut Parser, bytes: &[u8]) {
+    if !parser.ctx().local_echo() {
+        return;
+    }
+
+    for byte in bytes {
+        if let Err(err) = parser.consume(*byte) {
+            error!("failed to echo keyboard byte {byte:#04X}: {err}");
+        }
+    }
+}
+
 fn map_character(character: char) -> Option<u8> {
     if character.is_control() {
         return None;
@@ -313,3 +348,48 @@ fn map_named_key(code: KeyCode) -> Option<&'static [u8]> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teletel::terminal::LocalEcho;
+    use teletel_protocol::codes::routing::{KEYBOARD_EMITTER, SCREEN_RECEIVER};
+    use teletel_protocol::codes::{ESC, PRO3, ROUTING_OFF};
+    use teletel_protocol::parser::TerminalProfile;
+
+    #[test]
+    fn typed_keys_follow_the_keyboard_routing() {
+        let mut emulator = TerminalState::default().parser;
+        let mut host = Parser::from_profile(TerminalProfile::MINITEL_2);
+        let mut echo = LocalEcho::new();
+
+        let send = |emulator: &mut Parser, host: &mut Parser, bytes: &[u8]| {
+            for byte in bytes {
+                host.consume(*byte).unwrap();
+                emulator.consume(*byte).unwrap();
+            }
+        };
+
+        send(&mut emulator, &mut host, b"Nom:");
+        echo.feed(&mut host, &type_text(&mut emulator, "abc"));
+
+        for x in 1..=7 {
+            assert_eq!(
+                emulator.ctx().grid.cell(x, 1).content,
+                host.ctx().grid.cell(x, 1).content
+            );
+        }
+        assert_eq!(emulator.ctx().grid.cell(5, 1).content, 'a');
+        assert_eq!(emulator.ctx().cursor_x, host.ctx().cursor_x);
+
+        send(
+            &mut emulator,
+            &mut host,
+            &[ESC, PRO3, ROUTING_OFF, SCREEN_RECEIVER, KEYBOARD_EMITTER],
+        );
+        echo.feed(&mut host, &type_text(&mut emulator, "d"));
+
+        assert_eq!(emulator.ctx().cursor_x, 8);
+        assert_eq!(host.ctx().cursor_x, 8);
+    }
+}
@@ -1,8 +1,37 @@
 use std::io::{self, Read, Write};
 use std::net::{TcpListener, TcpStream};
+#[cfg(target_os = "linux")]
+use std::fs::File;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
 
 pub const EMULATOR_PORT: u16 = 3615;
 
+/// Connection to the host driving the emulator
+pub enum Transport {
+    Tcp(TcpTransport),
+    #[cfg(target_os = "linux")]
+    Pty(PtyTransport),
+}
+
+impl Transport {
+    pub fn read_available(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match self {
+            Transport::Tcp(transport) => transport.read_available(),
+            #[cfg(target_os = "linux")]
+            Transport::Pty(transport) => transport.read_available(),
+        }
+    }
+
+    pub fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Transport::Tcp(transport) => transport.write_all(bytes),
+            #[cfg(target_os = "linux")]
+            Transport::Pty(transport) => transport.write_all(bytes),
+        }
+    }
+}
+
 pub struct TcpTransport {
     listener: TcpListener,
     client: Option<TcpStream>,
@@ -56,6 +85,58 @@ impl TcpTransport {
     }
 }
 
+/// Pseudo-terminal whose slave side is opened by the host as the serial
+/// port of a Minitel, for services expecting a serial device
+#[cfg(target_os = "linux")]
+pub struct PtyTransport {
+    master: File,
+    /// Kept open so that reads do not fail until the host opens the
+    /// slave side
+    _slave: File,
+    path: PathBuf,
+}
+
+#[cfg(target_os = "linux")]
+impl PtyTransport {
+    pub fn open() -> io::Result<Self> {
+        use nix::fcntl::{fcntl, FcntlArg, OFlag};
+        use std::os::fd::AsRawFd;
+        use teletel::terminal::PtyPair;
+
+        //raw line at 1200 bauds like a Minitel turned on, the character
+        //size and parity are ignored by pseudo-terminals
+        let pair = PtyPair::open().map_err(io::Error::other)?;
+        fcntl(pair.master.as_raw_fd(), FcntlArg::F_SETFL(OFlag::O_NONBLOCK))?;
+
+        Ok(Self {
+            master: pair.master,
+            _slave: pair.slave,
+            path: pair.path,
+        })
+    }
+
+    /// Path of the device the host opens
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn read_available(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut buffer = [0u8; 1024];
+        match self.master.read(&mut buffer) {
+            Ok(0) => Ok(None),
+            //the line carries 7 bits characters
+            Ok(n) => Ok(Some(buffer[..n].iter().map(|byte| byte & 0x7F).collect())),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.master.write_all(bytes)?;
+        self.master.flush()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +146,29 @@ mod tests {
         let transport = TcpTransport::bind();
         assert!(transport.is_ok());
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pty_round_trip() {
+        let mut transport = PtyTransport::open().unwrap();
+        let mut host = File::options()
+            .read(true)
+            .write(true)
+            .open(transport.path())
+            .unwrap();
+
+        host.write_all(b"Bonjour").unwrap();
+        let mut received = Vec::new();
+        while received.len() < 7 {
+            if let Some(bytes) = transport.read_available().unwrap() {
+                received.extend(bytes);
+            }
+        }
+        assert_eq!(received, b"Bonjour");
+
+        transport.write_all(b"3615").unwrap();
+        let mut answer = [0; 4];
+        host.read_exact(&mut answer).unwrap();
+        assert_eq!(&answer, b"3615");
+    }
 }
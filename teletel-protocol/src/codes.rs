@@ -13,6 +13,8 @@ pub mod c0 {
     /// Starts an escaping sequence
     pub const ESC: u8 = 0x1B;
     pub const SEP: u8 = 0x13;
+    pub const SOH: u8 = 0x01;
+    pub const EOT: u8 = 0x04;
     /// Activates the G2 character set
     pub const SS2: u8 = 0x19;
 }
@@ -124,6 +126,10 @@ pub mod layout {
 
     pub const CSI: u8 = 0x5B;
 
+    /// Asks for the position of the cursor when preceded by ESC, the
+    /// terminal answers with `US <row> <column>`
+    pub const ENQ_CURSOR: u8 = 0x61;
+
     /// Erase characters from the cursor position to the end of the screen
     pub const CSI_J: [u8; 3] = [ESC, CSI, 0x4A];
 
@@ -160,7 +166,6 @@ pub mod proto {
     pub const B300: u8 = 0x52;
     pub const B1200: u8 = 0x64;
     pub const B4800: u8 = 0x76;
-    #[cfg(feature = "minitel2")]
     pub const B9600: u8 = 0x7F;
     pub const REQ_STATUS: u8 = 0x72;
     pub const RESP_STATUS: u8 = 0x73;
@@ -172,14 +177,122 @@ pub mod proto {
     pub const STATE_REQUEST: u8 = 0x72; //p143
     pub const STATE_RESPONSE: u8 = 0x73; //p143
     pub const RESET: u8 = 0x7F; //p145
+    pub const ENQROM: u8 = 0x7B;
     pub const SCREEN: u8 = 0x58;
-
+    pub const MIXED: u8 = 0x32; //p144
+    pub const TO_MIXED: u8 = 0x7D; //p144
+    pub const TO_VIDEOTEX: u8 = 0x7E; //p144
+    pub const MIXED_ACK: u8 = 0x70; //p144
+    pub const VIDEOTEX_ACK: u8 = 0x71; //p144
+    pub const TELEINFORMATIQUE: u8 = 0x31; //p144
+    pub const TO_TELEINFORMATIQUE: u8 = 0x7D; //p144
+    pub const ROUTING_OFF: u8 = 0x60;
+    pub const ROUTING_ON: u8 = 0x61;
+    pub const ROUTING_REQUEST: u8 = 0x62;
+    pub const ROUTING_RESPONSE: u8 = 0x63;
+
+    /// Set on the status bytes to keep them out of the C0 set
+    pub const STATUS_BYTE: u8 = 0b0100_0000; //p143
     pub const LOWERCASE: u8 = 0b0000_1000; //p143
     pub const PCE: u8 = 0b0000_0100; //p143
     pub const PAGE_MODE: u8 = 0b0000_0010; //p143
     pub const EIGHTY_COLUMNS: u8 = 0b0000_0001; //p143
 }
 
+/// Modules of the terminal between which data is routed (aiguillages).
+/// A route is switched with `ESC PRO3 ROUTING_ON|ROUTING_OFF <receiver> <emitter>`
+/// and the state of a receiver is requested with `ESC PRO2 ROUTING_REQUEST
+/// <receiver>`. The terminal answers both with `ESC PRO3 ROUTING_RESPONSE
+/// <receiver> <status>`, the status having one bit per connected emitter.
+pub mod routing {
+    pub const SCREEN_RECEIVER: u8 = 0x58;
+    pub const KEYBOARD_RECEIVER: u8 = 0x59;
+    pub const MODEM_RECEIVER: u8 = 0x5A;
+    pub const SOCKET_RECEIVER: u8 = 0x5B;
+
+    pub const SCREEN_EMITTER: u8 = 0x50;
+    pub const KEYBOARD_EMITTER: u8 = 0x51;
+    pub const MODEM_EMITTER: u8 = 0x52;
+    pub const SOCKET_EMITTER: u8 = 0x53;
+
+    pub const FROM_SCREEN: u8 = 0b0000_0001;
+    pub const FROM_KEYBOARD: u8 = 0b0000_0010;
+    pub const FROM_MODEM: u8 = 0b0000_0100;
+    pub const FROM_SOCKET: u8 = 0b0000_1000;
+}
+
+/// Codes used to download dynamically redefinable character sets (DRCS)
+/// and to designate them in place of the standard G0 and G1 sets. A
+/// download starts with `US DOWNLOAD 0x20 0x20 0x20 <set> DOWNLOAD_FORMAT`,
+/// followed by `US DOWNLOAD <code> GLYPH_SEPARATOR` and the glyphs of the
+/// consecutive characters, each one terminated by `GLYPH_SEPARATOR`.
+/// Glyphs are 8x10 bitmaps sent as up to 14 bytes of 6 bits (0x40 to 0x7F),
+/// missing trailing bytes being blank.
+///
+/// Only available on the Minitel 2.
+pub mod drcs {
+    pub const DOWNLOAD: u8 = 0x23;
+    pub const DOWNLOAD_FORMAT: u8 = 0x49;
+    pub const GLYPH_SEPARATOR: u8 = 0x30;
+    pub const DRCS_G0: u8 = 0x42;
+    pub const DRCS_G1: u8 = 0x43;
+    pub const DESIGNATE_G0: u8 = 0x28;
+    pub const DESIGNATE_G1: u8 = 0x29;
+    pub const STANDARD_G0: u8 = 0x40;
+    pub const STANDARD_G1: u8 = 0x63;
+}
+
+/// Codes of the error correction procedure (PCE), enabled with
+/// `ESC PRO2 START PROCEDURE`. The data sent to the terminal is then split
+/// in blocks of 15 bytes followed by a check byte and a NUL byte, blocks
+/// being numbered from 0 to 15. The terminal reports a corrupted block with
+/// `NACK <0x40 + number>` and ignores everything until the host answers
+/// `SYN SYN <0x40 + number>` and sends the blocks again from that one.
+///
+/// Documented on page 146.
+pub mod pce {
+    pub const NACK: u8 = 0x15;
+    pub const SYN: u8 = 0x16;
+    /// Offset added to the number of a block when it is sent
+    pub const BLOCK_NUMBER: u8 = 0x40;
+    /// Generator polynomial of the check byte, x^7 + x^3 + 1 without the
+    /// leading term
+    pub const POLYNOMIAL: u8 = 0b000_1001;
+}
+
+/// Codes specific to the téléinformatique standard, an ASCII mode close to
+/// the VT100 where the screen is driven by ANSI (ISO 6429) CSI sequences.
+/// The terminal enters it with `ESC PRO2 TELEINFORMATIQUE TO_TELEINFORMATIQUE`.
+///
+/// Documented on page 144.
+pub mod teleinformatique {
+    use super::c0::{ESC, SEP};
+    use super::layout::CSI;
+
+    /// Switches back to the teletel standard
+    pub const TO_TELETEL: [u8; 4] = [ESC, CSI, 0x3F, 0x7B];
+
+    /// Sent by the terminal once it switched to the téléinformatique standard
+    pub const TELEINFORMATIQUE_ACK: [u8; 4] = [ESC, CSI, 0x3F, 0x7A];
+
+    /// Sent by the terminal once it switched back to the teletel standard
+    pub const TELETEL_ACK: [u8; 2] = [SEP, 0x5E];
+
+    pub const CURSOR_UP: u8 = 0x41;
+    pub const CURSOR_DOWN: u8 = 0x42;
+    pub const CURSOR_FORWARD: u8 = 0x43;
+    pub const CURSOR_BACK: u8 = 0x44;
+    pub const CURSOR_POSITION: u8 = 0x48;
+    pub const HV_POSITION: u8 = 0x66;
+    pub const ERASE_DISPLAY: u8 = 0x4A;
+    pub const ERASE_LINE: u8 = 0x4B;
+    pub const SGR: u8 = 0x6D;
+    pub const SET_SCROLL_REGION: u8 = 0x72;
+    pub const SET_MODE: u8 = 0x68;
+    pub const RESET_MODE: u8 = 0x6C;
+    pub const PRIVATE: u8 = 0x3F;
+}
+
 /// Keyboard function key codes. Each function key sends a SEP (0x13)
 /// byte followed by a key-specific byte. These are the codes emitted
 /// by Minitel function keys when pressed during a session.
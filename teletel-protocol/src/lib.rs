@@ -1,3 +1,7 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 #[macro_use]
 mod macros;
 
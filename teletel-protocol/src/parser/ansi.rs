@@ -0,0 +1,604 @@
+use super::*;
+use crate::codes::teleinformatique::*;
+
+/// Subset of the ANSI (ISO 6429) sequences understood by the terminal once
+/// it switched to the téléinformatique standard. The screen behaves like a
+/// VT100: attributes are applied on each character and not on zones, there
+/// are no delimiters and semi-graphic characters are not available.
+#[derive(Eq, PartialEq, Debug)]
+pub(super) enum AnsiSequence {
+    Escape,
+    IncompleteCsi(Option<u8>, Vec<Option<u16>>),
+    Print(u8),
+    Backspace,
+    Tab,
+    LineFeed,
+    CarriageReturn,
+    Beep,
+    Ignored,
+    Index,
+    ReverseIndex,
+    NextLine,
+    SaveCursor,
+    RestoreCursor,
+    Reset,
+    Csi(AnsiCsi),
+}
+
+/// Single byte controls and escape sequences of the téléinformatique standard
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum AnsiControl {
+    Backspace,
+    Tab,
+    LineFeed,
+    Index,
+    ReverseIndex,
+    NextLine,
+    SaveCursor,
+    RestoreCursor,
+    Reset,
+}
+
+/// CSI sequences of the téléinformatique standard, parameters are kept as
+/// sent, missing cursor movement counts and positions default to 1
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum AnsiCsi {
+    CursorUp(u16),
+    CursorDown(u16),
+    CursorForward(u16),
+    CursorBack(u16),
+    CursorPosition(u16, u16),
+    EraseDisplay(u16),
+    EraseLine(u16),
+    SelectGraphicRendition(Vec<u16>),
+    SetScrollRegion(Option<u16>, Option<u16>),
+    SetMode(Option<u8>, Vec<u16>, bool),
+    SwitchToTeletel,
+}
+
+impl AnsiCsi {
+    fn new(private: Option<u8>, params: &[Option<u16>], byte: u8) -> Result<Self, Error> {
+        //missing parameters and parameters set to 0 default to 1 for cursor movements
+        let count = |index: usize| {
+            params
+                .get(index)
+                .copied()
+                .flatten()
+                .filter(|value| *value != 0)
+                .unwrap_or(1)
+        };
+        let value = |index: usize| params.get(index).copied().flatten();
+
+        let result = match (private, byte) {
+            (Some(PRIVATE), 0x7B) if params.is_empty() => AnsiCsi::SwitchToTeletel,
+            (None, CURSOR_UP) => AnsiCsi::CursorUp(count(0)),
+            (None, CURSOR_DOWN) => AnsiCsi::CursorDown(count(0)),
+            (None, CURSOR_FORWARD) => AnsiCsi::CursorForward(count(0)),
+            (None, CURSOR_BACK) => AnsiCsi::CursorBack(count(0)),
+            (None, CURSOR_POSITION | HV_POSITION) => AnsiCsi::CursorPosition(count(0), count(1)),
+            (None, ERASE_DISPLAY) => AnsiCsi::EraseDisplay(value(0).unwrap_or(0)),
+            (None, ERASE_LINE) => AnsiCsi::EraseLine(value(0).unwrap_or(0)),
+            (None, SGR) if params.is_empty() => AnsiCsi::SelectGraphicRendition(vec![0]),
+            (None, SGR) => AnsiCsi::SelectGraphicRendition(
+                params.iter().map(|param| param.unwrap_or(0)).collect(),
+            ),
+            (None, SET_SCROLL_REGION) => AnsiCsi::SetScrollRegion(value(0), value(1)),
+            (_, SET_MODE | RESET_MODE) => AnsiCsi::SetMode(
+                private,
+                params.iter().map(|param| param.unwrap_or(0)).collect(),
+                byte == SET_MODE,
+            ),
+            _ => err!(
+                InvalidSequence, "Unsupported or invalid CSI sequence ending with {:#04X} in téléinformatique standard",
+                byte
+            ),
+        };
+
+        Ok(result)
+    }
+}
+
+impl Parsable for AnsiSequence {
+    fn new(ctx: &Context, byte: u8) -> Result<Self, Error> {
+        if !Self::supports(ctx, byte) {
+            err!(
+                Unsupported,
+                "Sequence starting with {:#04X} is not in the téléinformatique standard",
+                byte
+            );
+        }
+
+        let result = match byte {
+            ESC => AnsiSequence::Escape,
+            0x20..=0x7E => AnsiSequence::Print(byte),
+            BS => AnsiSequence::Backspace,
+            HT => AnsiSequence::Tab,
+            LF | VT | FF => AnsiSequence::LineFeed,
+            CR => AnsiSequence::CarriageReturn,
+            BEEP => AnsiSequence::Beep,
+            //like on a VT100, unknown control characters and DEL are ignored
+            _ => AnsiSequence::Ignored,
+        };
+
+        Ok(result)
+    }
+
+    fn supports(ctx: &Context, _byte: u8) -> bool {
+        ctx.standard == Standard::Teleinformatique
+    }
+
+    fn consume(&mut self, _ctx: &Context, byte: u8) -> Result<Self, Error> {
+        let result = match self {
+            AnsiSequence::Escape => match byte {
+                CSI => AnsiSequence::IncompleteCsi(None, Vec::new()),
+                0x44 => AnsiSequence::Index,
+                0x4D => AnsiSequence::ReverseIndex,
+                0x45 => AnsiSequence::NextLine,
+                0x37 => AnsiSequence::SaveCursor,
+                0x38 => AnsiSequence::RestoreCursor,
+                0x63 => AnsiSequence::Reset,
+                _ => err!(
+                    InvalidSequence, "Invalid escaped sequence starting with {:#04X} in téléinformatique standard",
+                    byte
+                ),
+            },
+            AnsiSequence::IncompleteCsi(private, params) => match byte {
+                0x3C..=0x3F if private.is_none() && params.is_empty() => {
+                    AnsiSequence::IncompleteCsi(Some(byte), Vec::new())
+                }
+                0x30..=0x39 => {
+                    let mut params = mem::take(params);
+                    let digit = (byte - 0x30) as u16;
+
+                    match params.last_mut() {
+                        Some(Some(value)) => *value = value.saturating_mul(10).saturating_add(digit),
+                        Some(last) => *last = Some(digit),
+                        None => params.push(Some(digit)),
+                    }
+
+                    AnsiSequence::IncompleteCsi(*private, params)
+                }
+                0x3B => {
+                    let mut params = mem::take(params);
+
+                    //an empty first parameter is omitted, keep it as a default value
+                    if params.is_empty() {
+                        params.push(None);
+                    }
+
+                    params.push(None);
+                    AnsiSequence::IncompleteCsi(*private, params)
+                }
+                0x40..=0x7E => AnsiSequence::Csi(AnsiCsi::new(*private, params, byte)?),
+                _ => err!(
+                    InvalidSequence, "Unsupported or invalid byte {:#04X} for CSI sequence in téléinformatique standard",
+                    byte
+                ),
+            },
+            _ => err!(
+                InvalidSequence, "Sequence {:?} does not support additional bytes ({:#04X})",
+                self,
+                byte
+            ),
+        };
+
+        Ok(result)
+    }
+
+    fn is_complete(&self) -> bool {
+        !matches!(
+            self,
+            AnsiSequence::Escape | AnsiSequence::IncompleteCsi(_, _)
+        )
+    }
+}
+
+impl Context {
+    pub(super) fn consume_ansi(&mut self, sequence: &AnsiSequence) -> Result<(), Error> {
+        match sequence {
+            AnsiSequence::Print(byte) => self.print_ansi(*byte as char),
+            AnsiSequence::Backspace => self.cursor_x = self.cursor_x.saturating_sub(1).max(1),
+            AnsiSequence::Tab => {
+                //tab stops are set every 8 columns
+                self.cursor_x = ((self.cursor_x - 1) / 8 * 8 + 9).min(self.screen_width);
+            }
+            AnsiSequence::LineFeed | AnsiSequence::Index => self.line_feed_ansi(),
+            AnsiSequence::NextLine => {
+                self.line_feed_ansi();
+                self.cursor_x = 1;
+            }
+            AnsiSequence::ReverseIndex => {
+                let (top, bottom) = self.scroll_region;
+
+                if self.cursor_y == top {
+                    self.grid.scroll_region(top, bottom, -1);
+                } else if self.cursor_y > 1 {
+                    self.cursor_y -= 1;
+                }
+            }
+            AnsiSequence::CarriageReturn => self.cursor_x = 1,
+            AnsiSequence::Beep => self.beep = true,
+            AnsiSequence::SaveCursor => {
+                self.saved_cursor = Some(SavedState {
+                    cursor_x: self.cursor_x,
+                    cursor_y: self.cursor_y,
+                    attributes: self.attributes,
+                    pending_attributes: self.pending_attributes,
+                });
+            }
+            AnsiSequence::RestoreCursor => {
+                if let Some(saved_state) = self.saved_cursor {
+                    self.cursor_x = saved_state.cursor_x.min(self.screen_width);
+                    self.cursor_y = saved_state.cursor_y.min(self.screen_height);
+                    self.attributes = saved_state.attributes;
+                } else {
+                    self.cursor_x = 1;
+                    self.cursor_y = 1;
+                    self.attributes.reset();
+                }
+            }
+            AnsiSequence::Reset => {
+                self.reset_screen();
+                self.scroll_region = (1, self.screen_height);
+                self.saved_cursor = None;
+                self.insert = false;
+                self.cursor_x = 1;
+                self.cursor_y = 1;
+            }
+            AnsiSequence::Csi(csi) => self.consume_ansi_csi(csi)?,
+            AnsiSequence::Ignored => {}
+            _ => err!(Incomplete, "Received incomplete sequence {:?}", sequence),
+        }
+
+        Ok(())
+    }
+
+    fn consume_ansi_csi(&mut self, csi: &AnsiCsi) -> Result<(), Error> {
+        let width = self.screen_width as u16;
+        let height = self.screen_height as u16;
+
+        match csi {
+            //cursor movements never wrap nor scroll
+            AnsiCsi::CursorUp(count) => {
+                self.cursor_y = (self.cursor_y as u16).saturating_sub(*count).max(1) as u8
+            }
+            AnsiCsi::CursorDown(count) => {
+                self.cursor_y = (self.cursor_y as u16).saturating_add(*count).min(height) as u8
+            }
+            AnsiCsi::CursorForward(count) => {
+                self.cursor_x = (self.cursor_x as u16).saturating_add(*count).min(width) as u8
+            }
+            AnsiCsi::CursorBack(count) => {
+                self.cursor_x = (self.cursor_x as u16).saturating_sub(*count).max(1) as u8
+            }
+            AnsiCsi::CursorPosition(y, x) => {
+                self.cursor_x = (*x).min(width) as u8;
+                self.cursor_y = (*y).min(height) as u8;
+            }
+            AnsiCsi::EraseDisplay(0) => self.grid.clear_after(self.cursor_x, self.cursor_y),
+            AnsiCsi::EraseDisplay(1) => self.grid.clear_before(self.cursor_x, self.cursor_y),
+            AnsiCsi::EraseDisplay(2) => self.grid.reset(),
+            AnsiCsi::EraseLine(0) => self.grid.clear_in_row_after(self.cursor_x, self.cursor_y),
+            AnsiCsi::EraseLine(1) => self.grid.clear_in_row_before(self.cursor_x, self.cursor_y),
+            AnsiCsi::EraseLine(2) => self.grid.clear_row(self.cursor_y),
+            AnsiCsi::EraseDisplay(value) | AnsiCsi::EraseLine(value) => {
+                err!(
+                    InvalidSequence,
+                    "Invalid erase parameter {} in {:?}",
+                    value,
+                    csi
+                )
+            }
+            AnsiCsi::SelectGraphicRendition(params) => {
+                for param in params {
+                    self.select_graphic_rendition(*param)?;
+                }
+            }
+            AnsiCsi::SetScrollRegion(top, bottom) => {
+                let top = top.filter(|top| *top != 0).unwrap_or(1);
+                let bottom = bottom.filter(|bottom| *bottom != 0).unwrap_or(height);
+
+                if top >= bottom || bottom > height {
+                    err!(
+                        OutOfScreen,
+                        "Invalid scroll region from row {} to row {}",
+                        top,
+                        bottom
+                    );
+                }
+
+                self.scroll_region = (top as u8, bottom as u8);
+                self.cursor_x = 1;
+                self.cursor_y = 1;
+            }
+            AnsiCsi::SetMode(private, params, set) => {
+                for param in params {
+                    match (private, param) {
+                        (None, 4) => self.insert = *set,
+                        //send/receive mode, the keys are echoed when reset
+                        (None, 12) => self.routing.set(Module::Keyboard, Module::Screen, !*set),
+                        (Some(PRIVATE), 3) => self.set_columns(if *set { 40 } else { 80 }),
+                        _ => err!(
+                            Unsupported,
+                            "Unsupported mode {:?} {} in téléinformatique standard",
+                            private.map(|private| private as char),
+                            param
+                        ),
+                    }
+                }
+            }
+            AnsiCsi::SwitchToTeletel => self.switch_standard(Standard::Teletel)?,
+        }
+
+        Ok(())
+    }
+
+    fn select_graphic_rendition(&mut self, param: u16) -> Result<(), Error> {
+        match param {
+            0 => self.attributes.reset(),
+            //bold and normal intensity are not supported by the terminal
+            1 | 22 => {}
+            4 => self.attributes.underline = true,
+            5 => self.attributes.blinking = true,
+            7 => self.attributes.invert = true,
+            24 => self.attributes.underline = false,
+            25 => self.attributes.blinking = false,
+            27 => self.attributes.invert = false,
+            //ANSI colors are in the same order as the videotex ones
+            30..=37 => self.attributes.foreground = (param - 30) as u8,
+            39 => self.attributes.foreground = WHITE,
+            40..=47 => self.attributes.background = (param - 40) as u8,
+            49 => self.attributes.background = BLACK,
+            _ => err!(Unsupported, "Unsupported SGR parameter {}", param),
+        }
+
+        Ok(())
+    }
+
+    fn print_ansi(&mut self, character: char) {
+        if self.insert {
+            self.grid
+                .insert_after(1, self.cursor_x, self.cursor_y, self.attributes);
+        }
+
+        let cell = self.grid.cell_mut(self.cursor_x, self.cursor_y);
+        cell.set_content(character);
+        cell.set_delimiter(false);
+        cell.set_attributes(self.attributes);
+
+        if self.cursor_x == self.screen_width {
+            self.cursor_x = 1;
+            self.line_feed_ansi();
+        } else {
+            self.cursor_x += 1;
+        }
+    }
+
+    /// Moves the cursor down one row, scrolling the scroll region
+    /// if the cursor is on its bottom row
+    fn line_feed_ansi(&mut self) {
+        let (top, bottom) = self.scroll_region;
+
+        if self.cursor_y == bottom {
+            self.grid.scroll_region(top, bottom, 1);
+        } else if self.cursor_y < self.screen_height {
+            self.cursor_y += 1;
+        }
+    }
+
+    pub(super) fn set_columns(&mut self, columns: u8) {
+        self.screen_width = columns;
+        self.grid = Grid::new(self.screen_width, self.screen_height);
+        self.reset_screen();
+        self.cursor_x = 1;
+        self.cursor_y = 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn teleinformatique_parser() -> Parser {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        for byte in [ESC, PRO2, TELEINFORMATIQUE, TO_TELEINFORMATIQUE] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        parser
+    }
+
+    fn consume_all(parser: &mut Parser, bytes: &[u8]) {
+        for byte in bytes {
+            assert_eq!(parser.consume(*byte), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_switch_standard() {
+        let mut parser = teleinformatique_parser();
+
+        assert_eq!(parser.ctx().standard, Standard::Teleinformatique);
+        assert_eq!(parser.ctx().screen_width, 80);
+        assert_eq!(parser.ctx().scroll_region, (1, 24));
+        assert_eq!(parser.take_response(), TELEINFORMATIQUE_ACK.to_vec());
+
+        consume_all(&mut parser, &TO_TELETEL);
+
+        assert_eq!(parser.ctx().standard, Standard::Teletel);
+        assert_eq!(parser.ctx().mode, Mode::Videotex);
+        assert_eq!(parser.ctx().screen_width, 40);
+        assert_eq!(parser.take_response(), TELETEL_ACK.to_vec());
+    }
+
+    #[test]
+    fn test_set_standard_does_not_respond() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        parser.set_standard(Standard::Teleinformatique);
+
+        assert_eq!(parser.ctx().standard, Standard::Teleinformatique);
+        assert!(!parser.has_response());
+    }
+
+    #[test]
+    fn test_print_and_wrap() {
+        let mut parser = teleinformatique_parser();
+        consume_all(&mut parser, b"\x1B[1;79Habc");
+
+        assert_eq!(parser.ctx().grid.cell(79, 1).content, 'a');
+        assert_eq!(parser.ctx().grid.cell(80, 1).content, 'b');
+        assert_eq!(parser.ctx().grid.cell(1, 2).content, 'c');
+        assert!(!parser.ctx().grid.cell(1, 2).is_delimiter);
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (2, 2));
+    }
+
+    #[test]
+    fn test_cursor_position_and_movements() {
+        let mut parser = teleinformatique_parser();
+
+        consume_all(&mut parser, b"\x1B[10;20H");
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (20, 10));
+
+        consume_all(&mut parser, b"\x1B[H");
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (1, 1));
+
+        consume_all(&mut parser, b"\x1B[;5f\x1B[3B\x1B[2C");
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (7, 4));
+
+        //movements stop at the borders of the screen
+        consume_all(&mut parser, b"\x1B[99A\x1B[99D");
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (1, 1));
+
+        consume_all(&mut parser, b"\x1B[99;99H");
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (80, 24));
+
+        consume_all(&mut parser, b"\x1B[5;5H\tA\r\x08");
+        assert_eq!(parser.ctx().grid.cell(9, 5).content, 'A');
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (1, 5));
+    }
+
+    #[test]
+    fn test_select_graphic_rendition() {
+        let mut parser = teleinformatique_parser();
+        consume_all(&mut parser, b"\x1B[4;7;31;44mA\x1B[24mB\x1B[mC");
+
+        let a = parser.ctx().grid.cell(1, 1).attributes;
+        assert!(a.underline);
+        assert!(a.invert);
+        assert_eq!(a.foreground, RED);
+        assert_eq!(a.background, BLUE);
+
+        let b = parser.ctx().grid.cell(2, 1).attributes;
+        assert!(!b.underline);
+        assert!(b.invert);
+
+        let c = parser.ctx().grid.cell(3, 1).attributes;
+        assert!(!c.invert);
+        assert_eq!(c.foreground, WHITE);
+        assert_eq!(c.background, BLACK);
+    }
+
+    #[test]
+    fn test_erase_display_and_line() {
+        let mut parser = teleinformatique_parser();
+        consume_all(&mut parser, b"abcd\r\ndefg\x1B[1;3H\x1B[K");
+
+        assert_eq!(parser.ctx().grid.cell(2, 1).content, 'b');
+        assert!(parser.ctx().grid.cell(3, 1).is_delimiter);
+        assert_eq!(parser.ctx().grid.cell(4, 2).content, 'g');
+
+        consume_all(&mut parser, b"\x1B[1K");
+        assert!(parser.ctx().grid.cell(1, 1).is_delimiter);
+
+        consume_all(&mut parser, b"\x1B[2;2H\x1B[J");
+        assert_eq!(parser.ctx().grid.cell(1, 2).content, 'd');
+        assert!(parser.ctx().grid.cell(2, 2).is_delimiter);
+
+        consume_all(&mut parser, b"\x1B[2J");
+        assert!(parser.ctx().grid.cell(1, 2).is_delimiter);
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (2, 2));
+    }
+
+    #[test]
+    fn test_scroll_region() {
+        let mut parser = teleinformatique_parser();
+        consume_all(&mut parser, b"top\x1B[24;1Hbottom\x1B[2;4r");
+
+        assert_eq!(parser.ctx().scroll_region, (2, 4));
+        assert_eq!((parser.ctx().cursor_x, parser.ctx().cursor_y), (1, 1));
+
+        consume_all(&mut parser, b"\x1B[2;1Ha\r\nb\r\nc\r\nd");
+
+        //the first row of the region scrolled out, the rest is untouched
+        assert_eq!(parser.ctx().grid.cell(1, 1).content, 't');
+        assert_eq!(parser.ctx().grid.cell(1, 2).content, 'b');
+        assert_eq!(parser.ctx().grid.cell(1, 3).content, 'c');
+        assert_eq!(parser.ctx().grid.cell(1, 4).content, 'd');
+        assert_eq!(parser.ctx().grid.cell(1, 24).content, 'b');
+        assert_eq!(parser.ctx().cursor_y, 4);
+
+        //reverse index at the top of the region scrolls it down
+        consume_all(&mut parser, b"\x1B[2;1H\x1BM");
+        assert!(parser.ctx().grid.cell(1, 2).is_delimiter);
+        assert_eq!(parser.ctx().grid.cell(1, 3).content, 'b');
+        assert_eq!(parser.ctx().grid.cell(1, 4).content, 'c');
+        assert!(parser.ctx().grid.cell(1, 5).is_delimiter);
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut parser = teleinformatique_parser();
+
+        consume_all(&mut parser, b"\x1B[?3h");
+        assert_eq!(parser.ctx().screen_width, 40);
+        assert!(parser.ctx().grid.cell_opt(41, 1).is_none());
+
+        consume_all(&mut parser, b"\x1B[?3l");
+        assert_eq!(parser.ctx().screen_width, 80);
+        assert_eq!(parser.ctx().status() & EIGHTY_COLUMNS, EIGHTY_COLUMNS);
+    }
+
+    #[test]
+    fn test_echo_mode() {
+        let mut parser = teleinformatique_parser();
+
+        consume_all(&mut parser, b"\x1B[12h");
+        assert!(!parser.ctx().local_echo());
+
+        consume_all(&mut parser, b"\x1B[12l");
+        assert!(parser.ctx().local_echo());
+    }
+
+    #[test]
+    fn test_invalid_sequences() {
+        let ctx = teleinformatique_parser().ctx;
+
+        let mut sequence = AnsiSequence::new(&ctx, ESC).unwrap();
+        sequence = sequence.consume(&ctx, CSI).unwrap();
+        assert_err!(
+            sequence.consume(&ctx, 0x7C),
+            "Unsupported or invalid CSI sequence ending with 0x7C in téléinformatique standard"
+        );
+
+        assert_err!(
+            AnsiSequence::new(&Context::new(DisplayComponent::VGP5), b'A'),
+            "Sequence starting with 0x41 is not in the téléinformatique standard"
+        );
+
+        let mut ctx = ctx;
+        assert_err!(
+            ctx.consume_ansi(&AnsiSequence::Csi(AnsiCsi::SelectGraphicRendition(vec![
+                38
+            ]))),
+            "Unsupported SGR parameter 38"
+        );
+        assert_err!(
+            ctx.consume_ansi(&AnsiSequence::Csi(AnsiCsi::SetScrollRegion(
+                Some(5),
+                Some(2)
+            ))),
+            "Invalid scroll region from row 5 to row 2"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use super::Error;
+
+/// How the parser reacts to an invalid or unsupported sequence. Whatever
+/// the policy, the parser drops the offending byte and keeps going.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Policy {
+    /// The error is returned to the caller
+    Strict,
+    /// The error is reported to the diagnostics sink
+    Lenient,
+    /// The error is stored until retrieved with [`Parser::take_diagnostics`]
+    ///
+    /// [`Parser::take_diagnostics`]: super::Parser::take_diagnostics
+    Collect,
+}
+
+impl Default for Policy {
+    /// Strict when the `strict` feature is enabled, lenient otherwise
+    fn default() -> Self {
+        if cfg!(feature = "strict") {
+            Policy::Strict
+        } else {
+            Policy::Lenient
+        }
+    }
+}
+
+/// Receives the errors recovered by a lenient parser. Each error holds
+/// the offending byte and its offset in the stream.
+pub trait Diagnostics {
+    fn warning(&mut self, error: &Error);
+}
+
+impl<F: FnMut(&Error)> Diagnostics for F {
+    fn warning(&mut self, error: &Error) {
+        self(error)
+    }
+}
+
+/// Default sink writing warnings to the standard error output
+#[cfg(feature = "std")]
+pub struct Stderr;
+
+#[cfg(feature = "std")]
+impl Diagnostics for Stderr {
+    fn warning(&mut self, error: &Error) {
+        match error.offset() {
+            Some(offset) => eprintln!("{} (offset {})", error, offset),
+            None => eprintln!("{}", error),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use super::*;
+use crate::codes::drcs::*;
+
+/// A redefined character, each byte is a row of 8 pixels with the
+/// leftmost pixel in the most significant bit.
+pub type Glyph = [u8; 10];
+
+/// Number of bytes of 6 bits needed to send the 80 pixels of a glyph
+const GLYPH_BYTES: usize = 14;
+
+/// Glyphs downloaded for the G0 and G1 sets and whether the redefined
+/// sets are designated in place of the standard ones.
+#[derive(Clone, Debug)]
+pub struct Drcs {
+    g0: [Option<Glyph>; 96],
+    g1: [Option<Glyph>; 96],
+    g0_designated: bool,
+    g1_designated: bool,
+    loading_set: Option<CharacterSet>,
+    loading_code: Option<u8>,
+}
+
+impl Default for Drcs {
+    fn default() -> Self {
+        Self {
+            g0: [None; 96],
+            g1: [None; 96],
+            g0_designated: false,
+            g1_designated: false,
+            loading_set: None,
+            loading_code: None,
+        }
+    }
+}
+
+impl Drcs {
+    /// Returns the glyph downloaded for the given code of the given set
+    pub fn glyph(&self, set: CharacterSet, code: u8) -> Option<&Glyph> {
+        if !(0x20..=0x7F).contains(&code) {
+            return None;
+        }
+
+        let glyphs = match set {
+            CharacterSet::G0 => &self.g0,
+            CharacterSet::G1 => &self.g1,
+        };
+
+        glyphs[(code - 0x20) as usize].as_ref()
+    }
+
+    /// Returns true if the redefined set is used in place of the standard set
+    pub fn is_designated(&self, set: CharacterSet) -> bool {
+        match set {
+            CharacterSet::G0 => self.g0_designated,
+            CharacterSet::G1 => self.g1_designated,
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.loading_set.is_some() && self.loading_code.is_some()
+    }
+
+    fn designate(&mut self, set: CharacterSet, drcs: bool) {
+        match set {
+            CharacterSet::G0 => self.g0_designated = drcs,
+            CharacterSet::G1 => self.g1_designated = drcs,
+        }
+    }
+
+    fn start_loading(&mut self, set: CharacterSet) {
+        self.loading_set = Some(set);
+        self.loading_code = None;
+    }
+
+    fn stop_loading(&mut self) {
+        self.loading_set = None;
+        self.loading_code = None;
+    }
+
+    fn store(&mut self, glyph: Glyph) -> Result<(), Error> {
+        let (Some(set), Some(code)) = (self.loading_set, self.loading_code) else {
+            err!(
+                InvalidDownload,
+                "Received a DRCS glyph outside of a download"
+            );
+        };
+
+        if code > 0x7F {
+            err!(
+                InvalidDownload,
+                "Tried to download a DRCS glyph past the end of the {:?} set",
+                set
+            );
+        }
+
+        match set {
+            CharacterSet::G0 => self.g0[(code - 0x20) as usize] = Some(glyph),
+            CharacterSet::G1 => self.g1[(code - 0x20) as usize] = Some(glyph),
+        }
+
+        self.loading_code = Some(code + 1);
+
+        Ok(())
+    }
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub(super) enum DrcsSequence {
+    Incomplete,
+    IncompleteDefinition(u8),
+    IncompleteDefinitionSet(CharacterSet),
+    Definition(CharacterSet),
+    IncompleteStart(u8),
+    Start(u8),
+    IncompleteGlyph(Vec<u8>),
+    Glyph(Glyph),
+}
+
+impl DrcsSequence {
+    /// Starts a download sequence after `US DOWNLOAD` was received
+    pub(super) fn download() -> Self {
+        DrcsSequence::Incomplete
+    }
+}
+
+impl Parsable for DrcsSequence {
+    fn new(ctx: &Context, byte: u8) -> Result<Self, Error> {
+        if !Self::supports(ctx, byte) {
+            err!(InvalidDownload, "Invalid DRCS glyph byte {:#04X}", byte);
+        }
+
+        if byte == GLYPH_SEPARATOR {
+            //a glyph with no data is a blank glyph
+            Ok(DrcsSequence::Glyph([0; 10]))
+        } else {
+            Ok(DrcsSequence::IncompleteGlyph(vec![byte]))
+        }
+    }
+
+    fn supports(ctx: &Context, byte: u8) -> bool {
+        ctx.drcs.is_loading() && (byte == GLYPH_SEPARATOR || (0x40..=0x7F).contains(&byte))
+    }
+
+    fn consume(&mut self, _ctx: &Context, byte: u8) -> Result<Self, Error> {
+        let result = match self {
+            DrcsSequence::Incomplete => match byte {
+                0x20 => DrcsSequence::IncompleteDefinition(1),
+                0x21..=0x7F => DrcsSequence::IncompleteStart(byte),
+                _ => err!(InvalidSequence, "Invalid DRCS sequence starting with {:#04X}", byte),
+            },
+            DrcsSequence::IncompleteDefinition(count) if *count < 3 && byte == 0x20 => {
+                DrcsSequence::IncompleteDefinition(*count + 1)
+            }
+            DrcsSequence::IncompleteDefinition(3) => match byte {
+                DRCS_G0 => DrcsSequence::IncompleteDefinitionSet(CharacterSet::G0),
+                DRCS_G1 => DrcsSequence::IncompleteDefinitionSet(CharacterSet::G1),
+                _ => err!(InvalidSequence, "Invalid DRCS character set {:#04X}", byte),
+            },
+            DrcsSequence::IncompleteDefinitionSet(set) if byte == DOWNLOAD_FORMAT => {
+                DrcsSequence::Definition(*set)
+            }
+            DrcsSequence::IncompleteStart(code) if byte == GLYPH_SEPARATOR => {
+                DrcsSequence::Start(*code)
+            }
+            DrcsSequence::IncompleteGlyph(data) => match byte {
+                GLYPH_SEPARATOR => DrcsSequence::Glyph(decode_glyph(data)),
+                0x40..=0x7F if data.len() < GLYPH_BYTES => {
+                    let mut data = mem::take(data);
+                    data.push(byte);
+
+                    DrcsSequence::IncompleteGlyph(data)
+                }
+                _ => err!(
+                    InvalidDownload, "Invalid byte {:#04X} in DRCS glyph, expected at most {} bytes between 0x40 and 0x7F",
+                    byte,
+                    GLYPH_BYTES
+                ),
+            },
+            _ => err!(
+                InvalidSequence, "DRCS sequence {:?} does not support additional bytes ({:#04X})",
+                self,
+                byte
+            ),
+        };
+
+        Ok(result)
+    }
+
+    fn is_complete(&self) -> bool {
+        matches!(
+            self,
+            DrcsSequence::Definition(_) | DrcsSequence::Start(_) | DrcsSequence::Glyph(_)
+        )
+    }
+}
+
+/// Unpacks the 6 bits of each byte into the rows of a glyph
+fn decode_glyph(data: &[u8]) -> Glyph {
+    let mut glyph = [0; 10];
+
+    for (i, byte) in data.iter().enumerate() {
+        for bit in 0..6 {
+            let pixel = i * 6 + bit;
+
+            if pixel < 80 && byte & (0x20 >> bit) != 0 {
+                glyph[pixel / 8] |= 0x80 >> (pixel % 8);
+            }
+        }
+    }
+
+    glyph
+}
+
+impl Context {
+    pub(super) fn consume_drcs(&mut self, sequence: &DrcsSequence) -> Result<(), Error> {
+        match sequence {
+            DrcsSequence::Definition(set) => self.drcs.start_loading(*set),
+            DrcsSequence::Start(code) => {
+                if self.drcs.loading_set.is_none() {
+                    err!(
+                        InvalidDownload,
+                        "Received a DRCS start code without a character set definition"
+                    );
+                }
+
+                self.drcs.loading_code = Some(*code);
+            }
+            DrcsSequence::Glyph(glyph) => self.drcs.store(*glyph)?,
+            _ => err!(
+                Incomplete,
+                "Received incomplete DRCS sequence {:?}",
+                sequence
+            ),
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn designate(&mut self, set: CharacterSet, drcs: bool) {
+        self.drcs.designate(set, drcs);
+    }
+
+    /// Downloads are ended by any sequence other than a DRCS sequence
+    pub(super) fn stop_drcs_loading(&mut self) {
+        self.drcs.stop_loading();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARROW: Glyph = [
+        0b00011000, 0b00111100, 0b01111110, 0b11111111, 0b00011000, 0b00011000, 0b00011000,
+        0b00011000, 0b00011000, 0b00000000,
+    ];
+
+    //ARROW packed in bytes of 6 bits
+    const ARROW_DATA: [u8; 14] = [
+        0x46, 0x43, 0x71, 0x7E, 0x7F, 0x71, 0x60, 0x58, 0x46, 0x41, 0x60, 0x58, 0x40, 0x40,
+    ];
+
+    fn consume_all(parser: &mut Parser, bytes: &[u8]) {
+        for byte in bytes {
+            assert_eq!(parser.consume(*byte), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_decode_glyph() {
+        assert_eq!(decode_glyph(&ARROW_DATA), ARROW);
+        assert_eq!(decode_glyph(&ARROW_DATA[..12]), ARROW);
+        assert_eq!(decode_glyph(&[]), [0; 10]);
+    }
+
+    #[test]
+    fn test_download_glyphs() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        consume_all(
+            &mut parser,
+            &[US, DOWNLOAD, 0x20, 0x20, 0x20, DRCS_G0, DOWNLOAD_FORMAT],
+        );
+        consume_all(&mut parser, &[US, DOWNLOAD, 0x41, GLYPH_SEPARATOR]);
+        consume_all(&mut parser, &ARROW_DATA);
+        consume_all(&mut parser, &[GLYPH_SEPARATOR, 0x7F, GLYPH_SEPARATOR]);
+
+        let drcs = &parser.ctx().drcs;
+        assert_eq!(drcs.glyph(CharacterSet::G0, 0x41), Some(&ARROW));
+        assert_eq!(
+            drcs.glyph(CharacterSet::G0, 0x42),
+            Some(&[0b11111100, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        );
+        assert_eq!(drcs.glyph(CharacterSet::G0, 0x43), None);
+        assert_eq!(drcs.glyph(CharacterSet::G1, 0x41), None);
+
+        //the download ends with the next sequence and bytes are printed again
+        consume_all(&mut parser, &[US, 0x41, 0x41, b'A']);
+        assert_eq!(parser.ctx().grid.cell(1, 1).content, 'A');
+        assert!(!parser.ctx().grid.cell(1, 1).attributes.drcs);
+    }
+
+    #[test]
+    fn test_designation() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        consume_all(&mut parser, &[ESC, DESIGNATE_G0, 0x20, DRCS_G0, b'A']);
+        assert!(parser.ctx().drcs.is_designated(CharacterSet::G0));
+        assert!(!parser.ctx().drcs.is_designated(CharacterSet::G1));
+
+        consume_all(&mut parser, &[ESC, DESIGNATE_G0, STANDARD_G0, b'A']);
+        assert!(!parser.ctx().drcs.is_designated(CharacterSet::G0));
+
+        consume_all(&mut parser, &[ESC, DESIGNATE_G1, 0x20, DRCS_G1, SO, 0x21]);
+        assert!(parser.ctx().drcs.is_designated(CharacterSet::G1));
+
+        let cells = [
+            parser.ctx().grid.cell(1, 1),
+            parser.ctx().grid.cell(2, 1),
+            parser.ctx().grid.cell(3, 1),
+        ];
+        assert!(cells[0].attributes.drcs);
+        assert!(!cells[1].attributes.drcs);
+        assert!(cells[2].attributes.drcs);
+        assert_eq!(cells[2].attributes.character_set, CharacterSet::G1);
+    }
+
+    #[test]
+    fn test_invalid_sequences() {
+        let ctx = Context::new(DisplayComponent::VGP5);
+
+        let mut sequence = DrcsSequence::download();
+        let mut sequence = sequence.consume(&ctx, 0x20).unwrap();
+        assert_err!(
+            sequence.consume(&ctx, 0x41),
+            "DRCS sequence IncompleteDefinition(1) does not support additional bytes (0x41)"
+        );
+
+        let mut sequence = DrcsSequence::IncompleteGlyph(ARROW_DATA.to_vec());
+        assert_err!(
+            sequence.consume(&ctx, 0x40),
+            "Invalid byte 0x40 in DRCS glyph, expected at most 14 bytes between 0x40 and 0x7F"
+        );
+
+        let mut ctx = ctx;
+        assert_err!(
+            ctx.consume_drcs(&DrcsSequence::Glyph(ARROW)),
+            "Received a DRCS glyph outside of a download"
+        );
+        assert_err!(
+            ctx.consume_drcs(&DrcsSequence::Start(0x41)),
+            "Received a DRCS start code without a character set definition"
+        );
+
+        ctx.consume_drcs(&DrcsSequence::Definition(CharacterSet::G1))
+            .unwrap();
+        ctx.consume_drcs(&DrcsSequence::Start(0x7F)).unwrap();
+        ctx.consume_drcs(&DrcsSequence::Glyph(ARROW)).unwrap();
+        assert_err!(
+            ctx.consume_drcs(&DrcsSequence::Glyph(ARROW)),
+            "Tried to download a DRCS glyph past the end of the G1 set"
+        );
+    }
+}
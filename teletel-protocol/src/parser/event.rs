@@ -0,0 +1,387 @@
+use super::*;
+use core::borrow::Borrow;
+use core::ops::Range;
+
+/// A sequence decoded by the parser along with the offsets of the bytes it
+/// was decoded from, counted from the first byte consumed by the parser.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Event {
+    pub kind: EventKind,
+    pub span: Range<usize>,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[non_exhaustive]
+pub enum EventKind {
+    Print(char),
+    PrintSemiGraphic(u8),
+    SetCharacterSet(CharacterSet),
+    Repeat(u8),
+
+    /// Moves the cursor by one character with C0 codes, wrapping around the screen
+    MoveCursor(Direction),
+    /// Moves the cursor with CSI codes, stopping at the borders of the screen
+    CursorUp(u8),
+    CursorDown(u8),
+    CursorRight(u8),
+    CursorLeft(u8),
+    SetCursor {
+        x: u8,
+        y: u8,
+    },
+    CarriageReturn,
+    RecordSeparator,
+    SubSection {
+        row: u8,
+        column: u8,
+    },
+    VisibleCursor(bool),
+    RequestCursorPosition,
+
+    ClearScreen,
+    Erase(Erase),
+    FillRowWithSpaces,
+    InsertCharacters(u8),
+    DeleteCharacters(u8),
+    InsertRows(u8),
+    DeleteRows(u8),
+    InsertMode(bool),
+
+    Foreground(u8),
+    Background(u8),
+    Blink(bool),
+    Invert(bool),
+    NormalSize,
+    DoubleHeight,
+    DoubleWidth,
+    DoubleSize,
+    Underline(bool),
+    Mask(bool),
+    ScreenMasking(bool),
+    IgnoreSequences(bool),
+    Beep,
+
+    Reset,
+    RequestSpeed,
+    RequestStatus,
+    RequestIdentification,
+    SetSpeed(u8),
+    SwitchMode(Mode),
+    SwitchStandard(Standard),
+    PageMode(PageMode),
+    ErrorCorrection(bool),
+    Sleep(bool),
+    Route {
+        from: Module,
+        to: Module,
+        on: bool,
+    },
+    RequestRouting(Module),
+
+    DrcsDefinition(CharacterSet),
+    DrcsStart(u8),
+    DrcsGlyph(Glyph),
+    Designation {
+        set: CharacterSet,
+        drcs: bool,
+    },
+
+    AnsiControl(AnsiControl),
+    AnsiCsi(AnsiCsi),
+}
+
+/// Parts of the screen cleared by CSI codes (p95 and 96)
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Erase {
+    CursorToEndOfScreen,
+    StartOfScreenToCursor,
+    Screen,
+    CursorToEndOfRow,
+    StartOfRowToCursor,
+    Row,
+}
+
+impl Sequence {
+    /// Returns the public event for a complete sequence, padding and
+    /// ignored bytes do not produce any event
+    pub(super) fn to_event(&self) -> Option<EventKind> {
+        let kind = match self {
+            Sequence::Ansi(ansi) => match ansi {
+                AnsiSequence::Print(byte) => EventKind::Print(*byte as char),
+                AnsiSequence::CarriageReturn => EventKind::CarriageReturn,
+                AnsiSequence::Beep => EventKind::Beep,
+                AnsiSequence::Backspace => EventKind::AnsiControl(AnsiControl::Backspace),
+                AnsiSequence::Tab => EventKind::AnsiControl(AnsiControl::Tab),
+                AnsiSequence::LineFeed => EventKind::AnsiControl(AnsiControl::LineFeed),
+                AnsiSequence::Index => EventKind::AnsiControl(AnsiControl::Index),
+                AnsiSequence::ReverseIndex => EventKind::AnsiControl(AnsiControl::ReverseIndex),
+                AnsiSequence::NextLine => EventKind::AnsiControl(AnsiControl::NextLine),
+                AnsiSequence::SaveCursor => EventKind::AnsiControl(AnsiControl::SaveCursor),
+                AnsiSequence::RestoreCursor => EventKind::AnsiControl(AnsiControl::RestoreCursor),
+                AnsiSequence::Reset => EventKind::AnsiControl(AnsiControl::Reset),
+                AnsiSequence::Csi(csi) => EventKind::AnsiCsi(csi.clone()),
+                _ => return None,
+            },
+            Sequence::Drcs(drcs) => match drcs {
+                DrcsSequence::Definition(set) => EventKind::DrcsDefinition(*set),
+                DrcsSequence::Start(code) => EventKind::DrcsStart(*code),
+                DrcsSequence::Glyph(glyph) => EventKind::DrcsGlyph(*glyph),
+                _ => return None,
+            },
+            Sequence::Escaped(esc) => match esc {
+                EscapedSequence::Protocol(pro) => match pro {
+                    Protocol::Reset => EventKind::Reset,
+                    Protocol::RequestSpeed => EventKind::RequestSpeed,
+                    Protocol::RequestStatus => EventKind::RequestStatus,
+                    Protocol::RequestIdentification => EventKind::RequestIdentification,
+                    Protocol::SetSpeed(Some(speed)) => EventKind::SetSpeed(*speed),
+                    Protocol::SwitchMode(Some(mode)) => EventKind::SwitchMode(*mode),
+                    Protocol::SwitchStandard(Some(standard)) => {
+                        EventKind::SwitchStandard(*standard)
+                    }
+                    Protocol::Scroll(true) => EventKind::PageMode(PageMode::Scroll),
+                    Protocol::Scroll(false) => EventKind::PageMode(PageMode::Page),
+                    Protocol::Procedure(pce) => EventKind::ErrorCorrection(*pce),
+                    Protocol::Sleep(sleep) => EventKind::Sleep(*sleep),
+                    Protocol::Route(on, Some(to), Some(from)) => EventKind::Route {
+                        from: *from,
+                        to: *to,
+                        on: *on,
+                    },
+                    Protocol::RequestRouting(Some(to)) => EventKind::RequestRouting(*to),
+                    _ => return None,
+                },
+                EscapedSequence::Csi(csi) => match csi {
+                    Csi::MoveUp(count) => EventKind::CursorUp(*count),
+                    Csi::MoveDown(count) => EventKind::CursorDown(*count),
+                    Csi::MoveRight(count) => EventKind::CursorRight(*count),
+                    Csi::MoveLeft(count) => EventKind::CursorLeft(*count),
+                    Csi::SetCursor(x, y) => EventKind::SetCursor { x: *x, y: *y },
+                    Csi::InsertSpacesFromCursorToEol => EventKind::FillRowWithSpaces,
+                    Csi::ClearFromCursorToEos => EventKind::Erase(Erase::CursorToEndOfScreen),
+                    Csi::ClearFromSosToCursor => EventKind::Erase(Erase::StartOfScreenToCursor),
+                    Csi::ClearScreenKeepCursorPos => EventKind::Erase(Erase::Screen),
+                    Csi::ClearFromCursorToEol => EventKind::Erase(Erase::CursorToEndOfRow),
+                    Csi::ClearFromSolToCursor => EventKind::Erase(Erase::StartOfRowToCursor),
+                    Csi::ClearRow => EventKind::Erase(Erase::Row),
+                    Csi::ClearAfterCursor(count) => EventKind::DeleteCharacters(*count),
+                    Csi::InsertFromCursor(count) => EventKind::InsertCharacters(*count),
+                    Csi::StartInsert => EventKind::InsertMode(true),
+                    Csi::EndInsert => EventKind::InsertMode(false),
+                    Csi::EraseRowsFromCursor(count) => EventKind::DeleteRows(*count),
+                    Csi::InsertRowsFromCursor(count) => EventKind::InsertRows(*count),
+                    _ => return None,
+                },
+                EscapedSequence::Background(color) => EventKind::Background(*color),
+                EscapedSequence::Foreground(color) => EventKind::Foreground(*color),
+                EscapedSequence::Blink(blink) => EventKind::Blink(*blink),
+                EscapedSequence::Invert(invert) => EventKind::Invert(*invert),
+                EscapedSequence::NormalSize => EventKind::NormalSize,
+                EscapedSequence::DoubleHeight => EventKind::DoubleHeight,
+                EscapedSequence::DoubleWidth => EventKind::DoubleWidth,
+                EscapedSequence::DoubleSize => EventKind::DoubleSize,
+                EscapedSequence::Underline(underline) => EventKind::Underline(*underline),
+                EscapedSequence::Mask(mask) => EventKind::Mask(*mask),
+                EscapedSequence::GetCursorPosition => EventKind::RequestCursorPosition,
+                EscapedSequence::Ignore(Some(ignore)) => EventKind::IgnoreSequences(*ignore),
+                EscapedSequence::ScreenMasking(mask) => EventKind::ScreenMasking(*mask),
+                EscapedSequence::Designation(set, drcs) => EventKind::Designation {
+                    set: *set,
+                    drcs: *drcs,
+                },
+                _ => return None,
+            },
+            Sequence::SetCharacterSet(set) => EventKind::SetCharacterSet(*set),
+            Sequence::SpecialCharacter(character) => {
+                EventKind::Print(character.to_character().ok()?)
+            }
+            Sequence::SemiGraphicCharacter(character) => EventKind::PrintSemiGraphic(character.0),
+            Sequence::SimpleCharacter(character) => EventKind::Print(character.0 as char),
+            Sequence::MoveCursor(direction) => EventKind::MoveCursor(*direction),
+            Sequence::CarriageReturn => EventKind::CarriageReturn,
+            Sequence::RecordSeparator => EventKind::RecordSeparator,
+            Sequence::ClearScreen => EventKind::ClearScreen,
+            Sequence::SubSection(Some(row), Some(column)) => EventKind::SubSection {
+                row: *row,
+                column: *column,
+            },
+            Sequence::Repeat(Some(count)) => EventKind::Repeat(*count),
+            Sequence::Beep => EventKind::Beep,
+            Sequence::VisibleCursor(visible) => EventKind::VisibleCursor(*visible),
+            Sequence::ErrorCharacter => EventKind::Print('\u{7F}'),
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+}
+
+/// Iterator over the events decoded from the given bytes, returned
+/// by `Parser::consume_events`. Bytes are only consumed when the
+/// iterator is advanced.
+pub struct Events<'a, I> {
+    parser: &'a mut Parser,
+    bytes: I,
+}
+
+impl<'a, I> Events<'a, I> {
+    pub(super) fn new(parser: &'a mut Parser, bytes: I) -> Self {
+        Self { parser, bytes }
+    }
+}
+
+impl<I: Iterator> Iterator for Events<'_, I>
+where
+    I::Item: Borrow<u8>,
+{
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for byte in self.bytes.by_ref() {
+            match self.parser.consume_event(*byte.borrow()) {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(parser: &mut Parser, bytes: &[u8]) -> Vec<Event> {
+        parser
+            .consume_events(bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_events_have_spans() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        let bytes = [
+            b'A',
+            ESC,
+            RED_FOREGROUND,
+            0x00,
+            SS2,
+            ACUTE,
+            b'e',
+            ESC,
+            CSI,
+            0x30,
+            0x32,
+            0x3B,
+            0x30,
+            0x35,
+            0x48,
+            SO,
+            0x21,
+        ];
+
+        assert_eq!(
+            events(&mut parser, &bytes),
+            vec![
+                Event {
+                    kind: EventKind::Print('A'),
+                    span: 0..1,
+                },
+                Event {
+                    kind: EventKind::Foreground(RED),
+                    span: 1..3,
+                },
+                Event {
+                    kind: EventKind::Print('é'),
+                    span: 4..7,
+                },
+                Event {
+                    kind: EventKind::SetCursor { x: 5, y: 2 },
+                    span: 7..15,
+                },
+                Event {
+                    kind: EventKind::SetCharacterSet(CharacterSet::G1),
+                    span: 15..16,
+                },
+                Event {
+                    kind: EventKind::PrintSemiGraphic(0x21),
+                    span: 16..17,
+                },
+            ]
+        );
+
+        //offsets keep counting across calls
+        assert_eq!(
+            events(&mut parser, &[ESC, PRO1, REQ_STATUS]),
+            vec![Event {
+                kind: EventKind::RequestStatus,
+                span: 17..20,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_events_are_lazy() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        let mut events = parser.consume_events(b"AB");
+
+        assert_eq!(
+            events.next(),
+            Some(Ok(Event {
+                kind: EventKind::Print('A'),
+                span: 0..1,
+            }))
+        );
+
+        assert_eq!(parser.ctx().cursor_x, 2);
+    }
+
+    #[test]
+    fn test_events_in_teleinformatique_standard() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        let bytes = [ESC, PRO2, TELEINFORMATIQUE, TO_TELEINFORMATIQUE];
+        let mut received = events(&mut parser, &bytes);
+        received.extend(events(&mut parser, b"\x00\x1B[2;3Hx\n"));
+
+        assert_eq!(
+            received,
+            vec![
+                Event {
+                    kind: EventKind::SwitchStandard(Standard::Teleinformatique),
+                    span: 0..4,
+                },
+                Event {
+                    kind: EventKind::AnsiCsi(AnsiCsi::CursorPosition(2, 3)),
+                    span: 5..11,
+                },
+                Event {
+                    kind: EventKind::Print('x'),
+                    span: 11..12,
+                },
+                Event {
+                    kind: EventKind::AnsiControl(AnsiControl::LineFeed),
+                    span: 12..13,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    fn test_events_yield_errors() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        let received = parser.consume_events(&[ESC, 0x30, CR]).collect::<Vec<_>>();
+
+        assert!(received[0].is_err());
+        assert_eq!(
+            received[1],
+            Ok(Event {
+                kind: EventKind::CarriageReturn,
+                span: 2..3,
+            })
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use super::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Manufacturer {
+    Matra,
+    Rtic,
+    TelicAlcatel,
+    Thomson,
+    Ccs,
+    Fiet,
+    Fime,
+    Unitel,
+    Option,
+    Bull,
+    Telematique,
+    Desmet,
+    Other(u8),
+}
+
+impl Manufacturer {
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            b'A' => Manufacturer::Matra,
+            b'B' => Manufacturer::Rtic,
+            b'C' => Manufacturer::TelicAlcatel,
+            b'D' => Manufacturer::Thomson,
+            b'E' => Manufacturer::Ccs,
+            b'F' => Manufacturer::Fiet,
+            b'G' => Manufacturer::Fime,
+            b'H' => Manufacturer::Unitel,
+            b'I' => Manufacturer::Option,
+            b'J' => Manufacturer::Bull,
+            b'K' => Manufacturer::Telematique,
+            b'L' => Manufacturer::Desmet,
+            code => Manufacturer::Other(code),
+        }
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            Manufacturer::Matra => b'A',
+            Manufacturer::Rtic => b'B',
+            Manufacturer::TelicAlcatel => b'C',
+            Manufacturer::Thomson => b'D',
+            Manufacturer::Ccs => b'E',
+            Manufacturer::Fiet => b'F',
+            Manufacturer::Fime => b'G',
+            Manufacturer::Unitel => b'H',
+            Manufacturer::Option => b'I',
+            Manufacturer::Bull => b'J',
+            Manufacturer::Telematique => b'K',
+            Manufacturer::Desmet => b'L',
+            Manufacturer::Other(code) => *code,
+        }
+    }
+}
+
+impl Model {
+    /// Several codes designate the same model with a different keyboard
+    /// or speed, they are merged into a single variant.
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            b'b' | b'c' | b'r' => Model::Minitel1,
+            b'e' | b's' => Model::Minitel1Color,
+            b'd' | b'f' => Model::Minitel10,
+            b'g' => Model::Emulator,
+            b'j' => Model::Printer,
+            b't' => Model::Terminatel252,
+            b'u' => Model::Minitel1B,
+            b'v' => Model::Minitel2,
+            b'w' => Model::Minitel10B,
+            b'y' => Model::Minitel5,
+            b'z' => Model::Minitel12,
+            code => Model::Other(code),
+        }
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            Model::Minitel1 => b'r',
+            Model::Minitel1Color => b's',
+            Model::Minitel10 => b'f',
+            Model::Emulator => b'g',
+            Model::Printer => b'j',
+            Model::Terminatel252 => b't',
+            Model::Minitel1B => b'u',
+            Model::Minitel2 => b'v',
+            Model::Minitel10B => b'w',
+            Model::Minitel5 => b'y',
+            Model::Minitel12 => b'z',
+            Model::Other(code) => *code,
+        }
+    }
+}
+
+/// Answer of the terminal to the ROM identification request
+/// `ESC PRO1 ENQROM`, sent as `SOH <manufacturer> <model> <version> EOT`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Identification {
+    pub manufacturer: Manufacturer,
+    pub model: Model,
+    /// Version of the terminal software
+    pub version: u8,
+}
+
+impl Identification {
+    pub fn from_bytes([manufacturer, model, version]: [u8; 3]) -> Self {
+        Identification {
+            manufacturer: Manufacturer::from_code(manufacturer),
+            model: Model::from_code(model),
+            version,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 5] {
+        [
+            SOH,
+            self.manufacturer.code(),
+            self.model.code(),
+            self.version,
+            EOT,
+        ]
+    }
+
+    /// Profile matching the identified model, models that are not known to
+    /// support the Minitel 2 features are considered as Minitel 1B.
+    pub fn profile(&self) -> TerminalProfile {
+        let profile = match self.model {
+            Model::Minitel2 | Model::Minitel5 | Model::Minitel12 | Model::Emulator => {
+                TerminalProfile::MINITEL_2
+            }
+            _ => TerminalProfile::MINITEL_1B,
+        };
+
+        TerminalProfile {
+            model: self.model,
+            ..profile
+        }
+    }
+}
+
+impl Default for Identification {
+    fn default() -> Self {
+        Identification {
+            manufacturer: Manufacturer::TelicAlcatel,
+            model: Model::Minitel2,
+            version: b'1',
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identification() {
+        let identification = Identification::from_bytes([b'C', b'v', b'4']);
+
+        assert_eq!(identification.manufacturer, Manufacturer::TelicAlcatel);
+        assert_eq!(identification.model, Model::Minitel2);
+        assert_eq!(identification.version, b'4');
+        assert_eq!(identification.to_bytes(), [SOH, b'C', b'v', b'4', EOT]);
+        assert_eq!(identification.profile().model, Model::Minitel2);
+        assert!(identification.profile().supports(Feature::Drcs));
+
+        let identification = Identification::from_bytes([b'Z', b'u', b'2']);
+        assert_eq!(identification.manufacturer, Manufacturer::Other(b'Z'));
+        assert_eq!(identification.profile(), TerminalProfile::MINITEL_1B);
+    }
+
+    #[test]
+    fn test_identification_request_emits_response() {
+        let mut parser = Parser::from_profile(TerminalProfile::MINITEL_1B);
+
+        assert_eq!(parser.consume(ESC), Ok(()));
+        assert_eq!(parser.consume(PRO1), Ok(()));
+        assert_eq!(parser.consume(ENQROM), Ok(()));
+
+        assert_eq!(parser.take_response(), [SOH, b'C', b'u', b'1', EOT]);
+    }
+}
@@ -1,13 +1,48 @@
+use crate::codes::drcs::{
+    DESIGNATE_G0, DESIGNATE_G1, DOWNLOAD, DRCS_G0, DRCS_G1, STANDARD_G0, STANDARD_G1,
+};
+use crate::codes::pce::{BLOCK_NUMBER, NACK};
+use crate::codes::teleinformatique::{TELEINFORMATIQUE_ACK, TELETEL_ACK};
 use crate::codes::*;
-use std::fmt::{Debug, Display, Formatter, Result as FmtResult, Write};
-use std::mem;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::borrow::Borrow;
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult, Write};
+use core::mem;
 
 macro_rules! err {
-    ($($arg:tt)*) => {
-        return Err(Error::new(format!($($arg)*)))
+    ($kind:ident, $($arg:tt)*) => {
+        return Err(Error::new(ErrorKind::$kind, format!($($arg)*)))
     }
 }
 
+mod ansi;
+mod diagnostics;
+mod drcs;
+mod event;
+mod identification;
+mod pce;
+mod profile;
+mod routing;
+mod status;
+
+use ansi::AnsiSequence;
+use drcs::DrcsSequence;
+
+pub use ansi::{AnsiControl, AnsiCsi};
+#[cfg(feature = "std")]
+pub use diagnostics::Stderr;
+pub use diagnostics::{Diagnostics, Policy};
+pub use drcs::{Drcs, Glyph};
+pub use event::{Erase, Event, EventKind, Events};
+pub use identification::{Identification, Manufacturer};
+pub use pce::{Block, BlockReader};
+pub use profile::{Feature, Model, TerminalProfile};
+pub use routing::{Module, Routing};
+pub use status::Status;
+
 /// - If G2 character set is requested but the following code does not exist in G2 a
 ///   lower horizontal line will be displayed instead except if it's contained in C0 then
 ///   the C0 character is displayed and SS2 is ignored (??? end of p90).
@@ -23,14 +58,55 @@ macro_rules! err {
 ///   or exiting a section or a subsection
 /// - Double height, width or size characters are displayed from the bottom left corner
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
 pub struct Error {
+    kind: ErrorKind,
     msg: String,
+    byte: Option<u8>,
+    offset: Option<usize>,
+    sequence: Vec<u8>,
 }
 
 impl Error {
-    fn new<S: Into<String>>(msg: S) -> Error {
-        Error { msg: msg.into() }
+    fn new<S: Into<String>>(kind: ErrorKind, msg: S) -> Error {
+        Error {
+            kind,
+            msg: msg.into(),
+            byte: None,
+            offset: None,
+            sequence: Vec::new(),
+        }
+    }
+
+    fn at(mut self, byte: u8, offset: usize, sequence: &[u8]) -> Error {
+        self.byte = Some(byte);
+        self.offset = Some(offset);
+        self.sequence = sequence.to_vec();
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn message(&self) -> &str {
+        &self.msg
+    }
+
+    /// Byte that triggered the error
+    pub fn byte(&self) -> Option<u8> {
+        self.byte
+    }
+
+    /// Absolute offset of the byte that triggered the error
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// Bytes of the sequence being parsed when the error occurred,
+    /// including the offending byte
+    pub fn sequence(&self) -> &[u8] {
+        &self.sequence
     }
 }
 
@@ -40,6 +116,29 @@ impl Display for Error {
     }
 }
 
+impl core::error::Error for Error {}
+
+/// Category of a parsing error
+#[non_exhaustive]
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ErrorKind {
+    /// The byte can not continue or start a sequence
+    InvalidSequence,
+    /// The byte is not a valid character in the current character set
+    InvalidCharacter,
+    /// The sequence is valid but not supported by the terminal in its
+    /// current state or display component
+    Unsupported,
+    /// The sequence would move the cursor outside of the screen
+    OutOfScreen,
+    /// A repetition was requested without a previous character
+    NothingToRepeat,
+    /// Malformed or unexpected DRCS download
+    InvalidDownload,
+    /// The sequence was interpreted before being complete
+    Incomplete,
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum DisplayComponent {
     VGP2,
@@ -52,6 +151,32 @@ pub enum PageMode {
     Scroll,
 }
 
+/// Display mode of the Teletel standard. The mixte mode is an 80 columns
+/// mode restricted to the G0 character set (p144).
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Mode {
+    Videotex,
+    Mixte,
+}
+
+impl Mode {
+    pub fn screen_width(&self) -> u8 {
+        match self {
+            Mode::Videotex => 40,
+            Mode::Mixte => 80,
+        }
+    }
+}
+
+/// Standard the terminal is currently following. The teletel standard
+/// covers the videotex and mixte modes while the téléinformatique standard
+/// is an ASCII mode driven by ANSI escape sequences (p144).
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Standard {
+    Teletel,
+    Teleinformatique,
+}
+
 trait ToCharacter {
     fn to_character(&self) -> Result<char, Error>;
 }
@@ -75,7 +200,7 @@ impl Parsable for CharacterSet {
         match byte {
             SI => Ok(CharacterSet::G0),
             SO => Ok(CharacterSet::G1),
-            invalid => err!("Invalid character set {:#04X}", invalid),
+            invalid => err!(InvalidSequence, "Invalid character set {:#04X}", invalid),
         }
     }
 
@@ -85,6 +210,7 @@ impl Parsable for CharacterSet {
 
     fn consume(&mut self, _ctx: &Context, byte: u8) -> Result<Self, Error> {
         err!(
+            InvalidSequence,
             "Character set {:?} does not support more bytes ({:#04X})",
             self,
             byte
@@ -103,7 +229,7 @@ struct SimpleCharacter(u8);
 impl Parsable for SimpleCharacter {
     fn new(ctx: &Context, byte: u8) -> Result<Self, Error> {
         if !Self::supports(ctx, byte) {
-            err!("Invalid simple character {:#04X}", byte);
+            err!(InvalidCharacter, "Invalid simple character {:#04X}", byte);
         }
 
         Ok(SimpleCharacter(byte))
@@ -115,6 +241,7 @@ impl Parsable for SimpleCharacter {
 
     fn consume(&mut self, _ctx: &Context, byte: u8) -> Result<Self, Error> {
         err!(
+            InvalidSequence,
             "Simple character {:?} does not support more bytes ({:#04X})",
             self,
             byte
@@ -139,7 +266,11 @@ struct SemiGraphicCharacter(u8);
 impl Parsable for SemiGraphicCharacter {
     fn new(ctx: &Context, mut byte: u8) -> Result<Self, Error> {
         if !Self::supports(ctx, byte) {
-            err!("Invalid semi-graphic character {:#04X}", byte);
+            err!(
+                InvalidCharacter,
+                "Invalid semi-graphic character {:#04X}",
+                byte
+            );
         }
 
         if byte == 0x7F {
@@ -159,6 +290,7 @@ impl Parsable for SemiGraphicCharacter {
 
     fn consume(&mut self, _ctx: &Context, byte: u8) -> Result<Self, Error> {
         err!(
+            InvalidSequence,
             "Semi-graphic character {:?} does not support more bytes ({:#04X})",
             self,
             byte
@@ -207,11 +339,18 @@ enum SpecialCharacter {
 impl Parsable for SpecialCharacter {
     fn new(ctx: &Context, byte: u8) -> Result<Self, Error> {
         if !Self::supports(ctx, byte) {
-            err!("Invalid special character {:#04X}", byte);
+            err!(InvalidCharacter, "Invalid special character {:#04X}", byte);
         }
 
         if ctx.attributes.character_set == CharacterSet::G1 {
-            err!("Special characters are not supported in G1");
+            err!(Unsupported, "Special characters are not supported in G1");
+        }
+
+        if ctx.mode == Mode::Mixte {
+            err!(
+                Unsupported,
+                "Special characters are not supported in mixte mode"
+            );
         }
 
         Ok(SpecialCharacter::Incomplete)
@@ -222,6 +361,24 @@ impl Parsable for SpecialCharacter {
     }
 
     fn consume(&mut self, ctx: &Context, byte: u8) -> Result<Self, Error> {
+        self.decode(ctx.display_component, byte)
+    }
+
+    fn is_complete(&self) -> bool {
+        !matches!(
+            self,
+            SpecialCharacter::Incomplete
+                | SpecialCharacter::Grave(None)
+                | SpecialCharacter::Acute(None)
+                | SpecialCharacter::Circumflex(None)
+                | SpecialCharacter::Diaeresis(None)
+                | SpecialCharacter::Cedilla(None)
+        )
+    }
+}
+
+impl SpecialCharacter {
+    fn decode(&self, display_component: DisplayComponent, byte: u8) -> Result<Self, Error> {
         let result = match self {
             SpecialCharacter::Incomplete => match byte {
                 GRAVE => SpecialCharacter::Grave(None),
@@ -231,9 +388,7 @@ impl Parsable for SpecialCharacter {
                 CEDILLA => SpecialCharacter::Cedilla(None),
                 LOWER_OE => SpecialCharacter::LowerOE,
                 UPPER_OE => SpecialCharacter::UpperOE,
-                ESZETT if ctx.display_component == DisplayComponent::VGP5 => {
-                    SpecialCharacter::Eszett
-                }
+                ESZETT if display_component == DisplayComponent::VGP5 => SpecialCharacter::Eszett,
                 POUND => SpecialCharacter::Pound,
                 DOLLAR => SpecialCharacter::Dollar,
                 NUMBER_SIGN => SpecialCharacter::NumberSign,
@@ -241,7 +396,7 @@ impl Parsable for SpecialCharacter {
                 ARROW_UP => SpecialCharacter::ArrowUp,
                 ARROW_RIGHT => SpecialCharacter::ArrowRight,
                 ARROW_DOWN => SpecialCharacter::ArrowDown,
-                PARAGRAPH if ctx.display_component == DisplayComponent::VGP5 => {
+                PARAGRAPH if display_component == DisplayComponent::VGP5 => {
                     SpecialCharacter::Paragraph
                 }
                 DEGREE => SpecialCharacter::Degree,
@@ -250,32 +405,53 @@ impl Parsable for SpecialCharacter {
                 ONE_QUARTER => SpecialCharacter::OneQuarter,
                 ONE_HALF => SpecialCharacter::OneHalf,
                 THREE_QUARTERS => SpecialCharacter::ThreeQuarters,
-                _ => err!("Invalid special character {:#04X}", byte),
+                _ => err!(InvalidCharacter, "Invalid special character {:#04X}", byte),
             },
             SpecialCharacter::Grave(None) => match byte {
                 b'a' | b'e' | b'u' => SpecialCharacter::Grave(Some(byte)),
-                _ => err!("Invalid character for grave accent {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for grave accent {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Acute(None) => match byte {
                 b'e' => SpecialCharacter::Acute(Some(byte)),
-                _ => err!("Invalid character for acute accent {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for acute accent {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Circumflex(None) => match byte {
                 b'a' | b'e' | b'i' | b'o' | b'u' => SpecialCharacter::Circumflex(Some(byte)),
-                _ => err!("Invalid character for circumflex accent {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for circumflex accent {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Diaeresis(None) => match byte {
                 b'e' | b'i' => SpecialCharacter::Diaeresis(Some(byte)),
-                b'a' | b'o' | b'u' if ctx.display_component == DisplayComponent::VGP5 => {
+                b'a' | b'o' | b'u' if display_component == DisplayComponent::VGP5 => {
                     SpecialCharacter::Diaeresis(Some(byte))
                 }
-                _ => err!("Invalid character for diaeresis {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for diaeresis {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Cedilla(None) => match byte {
                 b'c' => SpecialCharacter::Cedilla(Some(byte)),
-                _ => err!("Invalid character for cedilla {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for cedilla {:#04X}",
+                    byte
+                ),
             },
             _ => err!(
+                InvalidSequence,
                 "Escaped sequence {:?} does not support more bytes ({:#04X})",
                 self,
                 byte
@@ -284,17 +460,24 @@ impl Parsable for SpecialCharacter {
 
         Ok(result)
     }
+}
 
-    fn is_complete(&self) -> bool {
-        !matches!(
-            self,
-            SpecialCharacter::Incomplete
-                | SpecialCharacter::Grave(None)
-                | SpecialCharacter::Acute(None)
-                | SpecialCharacter::Circumflex(None)
-                | SpecialCharacter::Diaeresis(None)
-                | SpecialCharacter::Cedilla(None)
-        )
+/// Decodes the bytes following SS2 into the character of the G2 set they
+/// represent, accents being composed with the letter that follows them.
+/// Returns `None` while more bytes are needed.
+pub fn special_character(
+    display_component: DisplayComponent,
+    bytes: &[u8],
+) -> Result<Option<char>, Error> {
+    let mut character = SpecialCharacter::Incomplete;
+    for byte in bytes {
+        character = character.decode(display_component, *byte)?;
+    }
+
+    if character.is_complete() {
+        character.to_character().map(Some)
+    } else {
+        Ok(None)
     }
 }
 
@@ -305,11 +488,19 @@ impl ToCharacter for SpecialCharacter {
                 b'a' => 'à',
                 b'e' => 'è',
                 b'u' => 'ù',
-                _ => err!("Invalid character for grave accent {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for grave accent {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Acute(Some(byte)) => match byte {
                 b'e' => 'é',
-                _ => err!("Invalid character for acute accent {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for acute accent {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Circumflex(Some(byte)) => match byte {
                 b'a' => 'â',
@@ -317,7 +508,11 @@ impl ToCharacter for SpecialCharacter {
                 b'i' => 'î',
                 b'o' => 'ô',
                 b'u' => 'û',
-                _ => err!("Invalid character for circumflex accent {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for circumflex accent {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Diaeresis(Some(byte)) => match byte {
                 b'a' => 'ä',
@@ -325,11 +520,19 @@ impl ToCharacter for SpecialCharacter {
                 b'i' => 'ï',
                 b'o' => 'ö',
                 b'u' => 'ü',
-                _ => err!("Invalid character for diaeresis {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for diaeresis {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::Cedilla(Some(byte)) => match byte {
                 b'c' => 'ç',
-                _ => err!("Invalid character for cedilla {:#04X}", byte),
+                _ => err!(
+                    InvalidCharacter,
+                    "Invalid character for cedilla {:#04X}",
+                    byte
+                ),
             },
             SpecialCharacter::LowerOE => 'œ',
             SpecialCharacter::UpperOE => 'Œ',
@@ -348,7 +551,7 @@ impl ToCharacter for SpecialCharacter {
             SpecialCharacter::OneQuarter => '¼',
             SpecialCharacter::OneHalf => '½',
             SpecialCharacter::ThreeQuarters => '¾',
-            _ => err!("Special character {:?} is not complete", self),
+            _ => err!(Incomplete, "Special character {:?} is not complete", self),
         };
 
         Ok(char)
@@ -362,12 +565,22 @@ enum Protocol {
     Pro3,
     Reset,
     RequestSpeed,
+    RequestStatus,
+    RequestIdentification,
     SetSpeed(Option<u8>),
+    SwitchMode(Option<Mode>),
+    SwitchStandard(Option<Standard>),
     Toggle2(bool),
     Toggle3(bool),
     Scroll(bool),
+    /// Enables or disables the error correction procedure (PCE)
+    Procedure(bool),
     ToggleScreen(bool),
     Sleep(bool),
+    /// Connects or disconnects an emitter to a receiver, the receiver
+    /// being sent first
+    Route(bool, Option<Module>, Option<Module>),
+    RequestRouting(Option<Module>),
 }
 
 impl Parsable for Protocol {
@@ -376,7 +589,11 @@ impl Parsable for Protocol {
             PRO1 => Protocol::Pro1,
             PRO2 => Protocol::Pro2,
             PRO3 => Protocol::Pro3,
-            _ => err!("Invalid protocol sequence starting with {:#04X}", byte),
+            _ => err!(
+                InvalidSequence,
+                "Invalid protocol sequence starting with {:#04X}",
+                byte
+            ),
         };
 
         Ok(result)
@@ -391,16 +608,23 @@ impl Parsable for Protocol {
             Protocol::Pro1 => match byte {
                 RESET => Protocol::Reset,
                 REQ_SPEED => Protocol::RequestSpeed,
+                REQ_STATUS => Protocol::RequestStatus,
+                ENQROM => Protocol::RequestIdentification,
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid PRO1 sequence starting with {:#04X}",
                     byte
                 ),
             },
             Protocol::Pro2 => match byte {
                 PROG => Protocol::SetSpeed(None),
+                MIXED => Protocol::SwitchMode(None),
+                TELEINFORMATIQUE => Protocol::SwitchStandard(None),
                 START => Protocol::Toggle2(true),
                 STOP => Protocol::Toggle2(false),
+                ROUTING_REQUEST => Protocol::RequestRouting(None),
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid PRO2 sequence starting with {:#04X}",
                     byte
                 ),
@@ -408,15 +632,49 @@ impl Parsable for Protocol {
             Protocol::Pro3 => match byte {
                 START => Protocol::Toggle3(true),
                 STOP => Protocol::Toggle3(false),
+                ROUTING_ON => Protocol::Route(true, None, None),
+                ROUTING_OFF => Protocol::Route(false, None, None),
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid PRO3 sequence starting with {:#04X}",
                     byte
                 ),
             },
             Protocol::SetSpeed(None) => Protocol::SetSpeed(Some(byte)),
+            Protocol::Route(on, None, None) => match Module::from_receiver_code(byte) {
+                Some(to) => Protocol::Route(*on, Some(to), None),
+                None => err!(InvalidSequence, "Invalid routing receiver {:#04X}", byte),
+            },
+            Protocol::Route(on, Some(to), None) => match Module::from_emitter_code(byte) {
+                Some(from) => Protocol::Route(*on, Some(*to), Some(from)),
+                None => err!(InvalidSequence, "Invalid routing emitter {:#04X}", byte),
+            },
+            Protocol::RequestRouting(None) => match Module::from_receiver_code(byte) {
+                Some(to) => Protocol::RequestRouting(Some(to)),
+                None => err!(InvalidSequence, "Invalid routing receiver {:#04X}", byte),
+            },
+            Protocol::SwitchMode(None) => match byte {
+                TO_MIXED => Protocol::SwitchMode(Some(Mode::Mixte)),
+                TO_VIDEOTEX => Protocol::SwitchMode(Some(Mode::Videotex)),
+                _ => err!(
+                    InvalidSequence,
+                    "Unsupported or invalid PRO2 mode switch sequence starting with {:#04X}",
+                    byte
+                ),
+            },
+            Protocol::SwitchStandard(None) => match byte {
+                TO_TELEINFORMATIQUE => Protocol::SwitchStandard(Some(Standard::Teleinformatique)),
+                _ => err!(
+                    InvalidSequence,
+                    "Unsupported or invalid PRO2 standard switch sequence starting with {:#04X}",
+                    byte
+                ),
+            },
             Protocol::Toggle2(value) => match byte {
                 SCROLL => Protocol::Scroll(*value),
+                PROCEDURE => Protocol::Procedure(*value),
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid PRO2 start/stop sequence starting with {:#04X}",
                     byte
                 ),
@@ -424,6 +682,7 @@ impl Parsable for Protocol {
             Protocol::Toggle3(value) => match byte {
                 SCREEN => Protocol::ToggleScreen(*value),
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid PRO3 start/stop sequence starting with {:#04X}",
                     byte
                 ),
@@ -431,11 +690,13 @@ impl Parsable for Protocol {
             Protocol::ToggleScreen(value) => match byte {
                 0x41 => Protocol::Sleep(*value),
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid protocol toggle screen sequence starting with {:#04X}",
                     byte
                 ),
             },
             _ => err!(
+                InvalidSequence,
                 "Protocol sequence {:?} does not support additional bytes ({:#04X})",
                 self,
                 byte
@@ -452,9 +713,13 @@ impl Parsable for Protocol {
                 | Protocol::Pro2
                 | Protocol::Pro3
                 | Protocol::SetSpeed(None)
+                | Protocol::SwitchMode(None)
+                | Protocol::SwitchStandard(None)
                 | Protocol::Toggle2(_)
                 | Protocol::Toggle3(_)
                 | Protocol::ToggleScreen(_)
+                | Protocol::Route(_, _, None)
+                | Protocol::RequestRouting(None)
         )
     }
 }
@@ -488,13 +753,14 @@ impl Parsable for Csi {
     fn new(ctx: &Context, byte: u8) -> Result<Self, Error> {
         if !Self::supports(ctx, byte) {
             err!(
+                InvalidSequence,
                 "Unsupported or invalid CSI sequence starting with {:#04X}",
                 byte
             );
         }
 
         if ctx.cursor_y == 0 {
-            err!("CSI codes are not supported in row 0"); //p95
+            err!(Unsupported, "CSI codes are not supported in row 0"); //p95
         }
 
         Ok(Csi::Incomplete)
@@ -512,6 +778,7 @@ impl Parsable for Csi {
                 0x4A => Csi::ClearFromCursorToEos,
                 0x4B => Csi::ClearFromCursorToEol,
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid CSI sequence starting with {:#04X}",
                     byte
                 ),
@@ -536,6 +803,7 @@ impl Parsable for Csi {
                 0x4D => Csi::EraseRowsFromCursor(*value),
                 0x4C => Csi::InsertRowsFromCursor(*value),
                 _ => err!(
+                    InvalidSequence,
                     "Unsupported or invalid byte {:#04X} for quantified CSI sequence",
                     byte
                 ),
@@ -548,6 +816,7 @@ impl Parsable for Csi {
                     Csi::SetCursor(*x, *y)
                 } else {
                     err!(
+                        OutOfScreen,
                         "Invalid cursor position ({}, {}) for screen size ({}, {})",
                         x,
                         y,
@@ -560,6 +829,7 @@ impl Parsable for Csi {
             //TODO: implement other CSI sequences
             //TODO: implement end-of-page 95 recommendations but not here
             _ => err!(
+                InvalidSequence,
                 "Unsupported or invalid byte {:#04X} for sequence {:?}",
                 byte,
                 self
@@ -598,12 +868,15 @@ enum EscapedSequence {
     Ignore(Option<bool>),
     IncompleteScreenMasking(u8),
     ScreenMasking(bool),
+    IncompleteDesignation(CharacterSet, bool),
+    Designation(CharacterSet, bool),
 }
 
 impl Parsable for EscapedSequence {
     fn new(ctx: &Context, byte: u8) -> Result<Self, Error> {
         if !Self::supports(ctx, byte) {
             err!(
+                InvalidSequence,
                 "Unsupported or invalid escaped sequence starting with {:#04X}",
                 byte
             );
@@ -635,11 +908,17 @@ impl Parsable for EscapedSequence {
                 UNMASK => EscapedSequence::Mask(false),
                 PRO1 | PRO2 | PRO3 => EscapedSequence::Protocol(Protocol::new(ctx, byte)?),
                 CSI => EscapedSequence::Csi(Csi::new(ctx, byte)?),
-                0x61 => EscapedSequence::GetCursorPosition,
+                ENQ_CURSOR => EscapedSequence::GetCursorPosition,
                 0x25 => EscapedSequence::Ignore(None),
                 0x2F => EscapedSequence::IncompleteStopIgnore,
                 0x23 => EscapedSequence::IncompleteScreenMasking(1),
-                _ => err!("Invalid escaped sequence starting with {:#04X}", byte),
+                DESIGNATE_G0 => EscapedSequence::IncompleteDesignation(CharacterSet::G0, false),
+                DESIGNATE_G1 => EscapedSequence::IncompleteDesignation(CharacterSet::G1, false),
+                _ => err!(
+                    InvalidSequence,
+                    "Invalid escaped sequence starting with {:#04X}",
+                    byte
+                ),
             },
             EscapedSequence::IncompleteStopIgnore if byte == 0x3F => {
                 EscapedSequence::Ignore(Some(false))
@@ -654,11 +933,38 @@ impl Parsable for EscapedSequence {
                 MASK => EscapedSequence::ScreenMasking(true),
                 UNMASK => EscapedSequence::ScreenMasking(false),
                 _ => err!(
+                    InvalidSequence,
                     "Invalid screen masking byte ({:#04X}), expected 0x58 or 0x5F",
                     byte
                 ),
             },
+            EscapedSequence::IncompleteDesignation(set, false) => match (*set, byte) {
+                (set, 0x20) => EscapedSequence::IncompleteDesignation(set, true),
+                (CharacterSet::G0, STANDARD_G0) => {
+                    EscapedSequence::Designation(CharacterSet::G0, false)
+                }
+                (CharacterSet::G1, STANDARD_G1) => {
+                    EscapedSequence::Designation(CharacterSet::G1, false)
+                }
+                _ => err!(
+                    InvalidSequence,
+                    "Invalid standard {:?} set designation byte {:#04X}",
+                    set,
+                    byte
+                ),
+            },
+            EscapedSequence::IncompleteDesignation(set, true) => match (*set, byte) {
+                (CharacterSet::G0, DRCS_G0) => EscapedSequence::Designation(CharacterSet::G0, true),
+                (CharacterSet::G1, DRCS_G1) => EscapedSequence::Designation(CharacterSet::G1, true),
+                _ => err!(
+                    InvalidSequence,
+                    "Invalid DRCS {:?} set designation byte {:#04X}",
+                    set,
+                    byte
+                ),
+            },
             _ => err!(
+                InvalidSequence,
                 "Escaped sequence {:?} does not support additional bytes ({:#04X})",
                 self,
                 byte
@@ -676,13 +982,14 @@ impl Parsable for EscapedSequence {
             EscapedSequence::IncompleteStopIgnore => false,
             EscapedSequence::Ignore(None) => false,
             EscapedSequence::IncompleteScreenMasking(_) => false,
+            EscapedSequence::IncompleteDesignation(_, _) => false,
             _ => true,
         }
     }
 }
 
-#[derive(Eq, PartialEq, Debug)]
-enum Direction {
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Direction {
     Up,
     Down,
     Right,
@@ -692,6 +999,8 @@ enum Direction {
 #[derive(Eq, PartialEq, Debug)]
 enum Sequence {
     Incomplete,
+    Ansi(AnsiSequence),
+    Drcs(DrcsSequence),
     Escaped(EscapedSequence),
     SetCharacterSet(CharacterSet),
     SpecialCharacter(SpecialCharacter),
@@ -720,7 +1029,11 @@ impl Parsable for Sequence {
     fn consume(&mut self, ctx: &Context, byte: u8) -> Result<Self, Error> {
         let result = match self {
             Sequence::Incomplete => {
-                if EscapedSequence::supports(ctx, byte) {
+                if AnsiSequence::supports(ctx, byte) {
+                    Sequence::Ansi(AnsiSequence::new(ctx, byte)?)
+                } else if DrcsSequence::supports(ctx, byte) {
+                    Sequence::Drcs(DrcsSequence::new(ctx, byte)?)
+                } else if EscapedSequence::supports(ctx, byte) {
                     Sequence::Escaped(EscapedSequence::new(ctx, byte)?)
                 } else if CharacterSet::supports(ctx, byte) {
                     Sequence::SetCharacterSet(CharacterSet::new(ctx, byte)?)
@@ -762,11 +1075,14 @@ impl Parsable for Sequence {
                     Sequence::VisibleCursor(false)
                 } else {
                     err!(
+                        InvalidSequence,
                         "Unsupported or invalid sequence starting with {:#04X}",
                         byte
                     )
                 }
             }
+            Sequence::Ansi(ansi_sequence) => Sequence::Ansi(ansi_sequence.consume(ctx, byte)?),
+            Sequence::Drcs(drcs_sequence) => Sequence::Drcs(drcs_sequence.consume(ctx, byte)?),
             Sequence::Escaped(escaped_sequence) => {
                 Sequence::Escaped(escaped_sequence.consume(ctx, byte)?)
             }
@@ -782,6 +1098,9 @@ impl Parsable for Sequence {
             Sequence::SimpleCharacter(simple_character) => {
                 Sequence::SimpleCharacter(simple_character.consume(ctx, byte)?)
             }
+            Sequence::SubSection(None, None) if byte == DOWNLOAD => {
+                Sequence::Drcs(DrcsSequence::download())
+            }
             Sequence::SubSection(None, None) if (0x40..=0x7F).contains(&byte) => {
                 Sequence::SubSection(Some(byte - 0x40), None)
             }
@@ -793,12 +1112,14 @@ impl Parsable for Sequence {
                     Sequence::Repeat(Some(byte - 0x40))
                 } else {
                     err!(
+                        InvalidSequence,
                         "Repeat sequence expects a number between 0x40 and 0x7F, got {:#04X}",
                         byte
                     )
                 }
             }
             _ => err!(
+                InvalidSequence,
                 "Sequence {:?} does not support additional bytes ({:#04X})",
                 self,
                 byte
@@ -811,6 +1132,8 @@ impl Parsable for Sequence {
     fn is_complete(&self) -> bool {
         match self {
             Sequence::Incomplete => false,
+            Sequence::Ansi(ansi_sequence) => ansi_sequence.is_complete(),
+            Sequence::Drcs(drcs_sequence) => drcs_sequence.is_complete(),
             Sequence::Escaped(escaped_sequence) => escaped_sequence.is_complete(),
             Sequence::SetCharacterSet(character_set) => character_set.is_complete(),
             Sequence::SpecialCharacter(special_character) => special_character.is_complete(),
@@ -874,6 +1197,7 @@ pub struct Attributes {
     pub double_width: bool,
     pub invert: bool,
     pub mask: bool,
+    pub drcs: bool,
 }
 
 impl Default for Attributes {
@@ -888,6 +1212,7 @@ impl Default for Attributes {
             double_width: false,
             invert: false,
             mask: false,
+            drcs: false,
         }
     }
 }
@@ -978,6 +1303,10 @@ impl Debug for Attributes {
             f.write_str(", mask")?;
         }
 
+        if self.drcs {
+            f.write_str(", drcs")?;
+        }
+
         f.write_char(')')
     }
 }
@@ -1048,11 +1377,23 @@ impl Debug for Cell {
     }
 }
 
+/// Number of cells of the largest screen, 80 columns by 24 rows
+pub const MAX_CELLS: usize = 80 * 24;
+
+#[cfg(not(feature = "fixed-grid"))]
+type Cells = Vec<Cell>;
+
+#[cfg(feature = "fixed-grid")]
+type Cells = [Cell; MAX_CELLS];
+
+/// Cells of the screen. With the `fixed-grid` feature the cells are stored
+/// in an array of [`MAX_CELLS`] cells that is never reallocated, which is
+/// better suited to microcontrollers.
 #[derive(Debug)]
 pub struct Grid {
     width: usize,
     height: usize,
-    data: Vec<Cell>,
+    data: Cells,
 }
 
 impl Grid {
@@ -1060,13 +1401,31 @@ impl Grid {
         let width = width as usize;
         let height = height as usize;
 
+        assert!(
+            width * height <= MAX_CELLS,
+            "Invalid grid size {}x{}",
+            width,
+            height
+        );
+
         Self {
             width,
             height,
+            #[cfg(not(feature = "fixed-grid"))]
             data: vec![Cell::delimiter(BLACK); width * height],
+            #[cfg(feature = "fixed-grid")]
+            data: [Cell::delimiter(BLACK); MAX_CELLS],
         }
     }
 
+    fn cells(&self) -> &[Cell] {
+        &self.data[..self.width * self.height]
+    }
+
+    fn cells_mut(&mut self) -> &mut [Cell] {
+        &mut self.data[..self.width * self.height]
+    }
+
     pub fn cell(&self, x: u8, y: u8) -> &Cell {
         assert!(
             x >= 1 && x <= self.width as u8,
@@ -1079,7 +1438,7 @@ impl Grid {
             y
         );
 
-        &self.data[(y as usize - 1) * self.width + x as usize - 1]
+        &self.cells()[(y as usize - 1) * self.width + x as usize - 1]
     }
 
     pub fn previous_cell(&self, x: u8, y: u8) -> Option<&Cell> {
@@ -1098,7 +1457,7 @@ impl Grid {
         if offset == 0 {
             None
         } else {
-            Some(&self.data[offset - 1])
+            Some(&self.cells()[offset - 1])
         }
     }
 
@@ -1107,7 +1466,7 @@ impl Grid {
             return None;
         }
 
-        self.data
+        self.cells()
             .get((y as usize - 1) * self.width + x as usize - 1)
     }
 
@@ -1123,33 +1482,37 @@ impl Grid {
             y
         );
 
-        &mut self.data[(y as usize - 1) * self.width + x as usize - 1]
+        let width = self.width;
+        &mut self.cells_mut()[(y as usize - 1) * width + x as usize - 1]
     }
 
     fn reset(&mut self) {
-        self.data
-            .iter_mut()
-            .for_each(|cell| *cell = Cell::delimiter(BLACK));
+        self.cells_mut().fill(Cell::delimiter(BLACK));
     }
 
     fn scroll(&mut self, count: i8) {
-        if count == 0 {
-            return;
-        }
+        self.scroll_region(1, self.height as u8, count);
+    }
 
+    /// Scrolls the rows between `top` and `bottom` (inclusive) by the given
+    /// amount of rows, leaving the rest of the screen untouched
+    fn scroll_region(&mut self, top: u8, bottom: u8, count: i8) {
+        let start = (top as usize - 1) * self.width;
+        let end = bottom as usize * self.width;
         let cells_to_scroll = count.unsigned_abs() as usize * self.width;
 
         if count > 0 {
-            self.data.splice(0..cells_to_scroll, vec![]);
-            self.data.splice(
-                self.data.len()..,
-                vec![Cell::delimiter(BLACK); cells_to_scroll],
+            shift_left(
+                &mut self.cells_mut()[start..end],
+                cells_to_scroll,
+                Cell::delimiter(BLACK),
             );
         } else {
-            self.data
-                .splice((self.data.len() - cells_to_scroll).., vec![]);
-            self.data
-                .splice(0..0, vec![Cell::delimiter(BLACK); cells_to_scroll]);
+            shift_right(
+                &mut self.cells_mut()[start..end],
+                cells_to_scroll,
+                Cell::delimiter(BLACK),
+            );
         }
     }
 
@@ -1175,64 +1538,50 @@ impl Grid {
 
     fn insert_after(&mut self, count: u8, x: u8, y: u8, attributes: Attributes) {
         let row_start = (y as usize - 1) * self.width;
+        let insert_at = row_start + x as usize - 1;
         let row_end = row_start + self.width;
-        let max_insert = self.width as u8 - (x - 1);
-        let count = count.min(max_insert);
-
-        if count == 0 {
-            return;
-        }
 
-        let mut row = self.data[row_start..row_end].to_vec();
-        let insert_at = x as usize - 1;
-        row.splice(
-            insert_at..insert_at,
-            vec![Cell::space(attributes, false); count as usize],
+        shift_right(
+            &mut self.cells_mut()[insert_at..row_end],
+            count as usize,
+            Cell::space(attributes, false),
         );
-        row.truncate(self.width);
-
-        self.data.splice(row_start..row_end, row);
     }
 
     fn delete_after(&mut self, count: u8, x: u8, y: u8, attributes: Attributes) {
         let row_start = (y as usize - 1) * self.width;
+        let delete_from = row_start + x as usize - 1;
         let row_end = row_start + self.width;
-        let max_delete = self.width as u8 - (x - 1);
-        let count = count.min(max_delete);
-
-        if count == 0 {
-            return;
-        }
 
-        let mut row = self.data[row_start..row_end].to_vec();
-        let delete_from = x as usize - 1;
-        row.drain(delete_from..delete_from + count as usize);
-        row.extend(std::iter::repeat_n(
-            Cell::space(attributes, false),
+        shift_left(
+            &mut self.cells_mut()[delete_from..row_end],
             count as usize,
-        ));
-        row.truncate(self.width);
-
-        self.data.splice(row_start..row_end, row);
+            Cell::space(attributes, false),
+        );
     }
 
     fn insert_rows_after(&mut self, count: u8, y: u8) {
         //don't -1 to y because we're inserting after the given row
         let start = (y as usize) * self.width;
-        self.data.splice(
-            start..start,
-            vec![Cell::delimiter(BLACK); count as usize * self.width],
+        let cells_to_insert = count as usize * self.width;
+
+        shift_right(
+            &mut self.cells_mut()[start..],
+            cells_to_insert,
+            Cell::delimiter(BLACK),
         );
-        self.data.truncate(self.width * self.height);
     }
 
     fn delete_rows_after(&mut self, count: u8, y: u8) {
         //don't -1 to y because we're deleting after the given row
         let start = (y as usize) * self.width;
-        let end = start + count as usize * self.width;
-        self.data.splice(start..end, vec![]);
-        self.data
-            .resize(self.width * self.height, Cell::delimiter(BLACK));
+        let cells_to_delete = count as usize * self.width;
+
+        shift_left(
+            &mut self.cells_mut()[start..],
+            cells_to_delete,
+            Cell::delimiter(BLACK),
+        );
     }
 
     fn recalculate_row_attributes(&mut self, x: u8, y: u8) {
@@ -1248,7 +1597,7 @@ impl Grid {
             cell.attributes
         };
 
-        for cell in &mut self.data[start..=end] {
+        for cell in &mut self.cells_mut()[start..=end] {
             if cell.content == '\0' {
                 cell.reset();
                 attributes.reset_zone_attributes();
@@ -1277,28 +1626,60 @@ impl Grid {
         let start = (y1 - 1) as usize * self.width + (x1 - 1) as usize;
         let end = (y2 - 1) as usize * self.width + (x2 - 1) as usize;
 
-        self.data[start..=end].iter_mut()
+        self.cells_mut()[start..=end].iter_mut()
     }
 }
 
+/// Moves the cells of the slice `count` cells to the left, filling the
+/// cells left empty at the end with the given cell
+fn shift_left(cells: &mut [Cell], count: usize, fill: Cell) {
+    let count = count.min(cells.len());
+    let len = cells.len();
+
+    cells.rotate_left(count);
+    cells[len - count..].fill(fill);
+}
+
+/// Moves the cells of the slice `count` cells to the right, filling the
+/// cells left empty at the start with the given cell
+fn shift_right(cells: &mut [Cell], count: usize, fill: Cell) {
+    let count = count.min(cells.len());
+
+    cells.rotate_right(count);
+    cells[..count].fill(fill);
+}
+
 #[derive(Debug)]
 pub struct Context {
     pub display_component: DisplayComponent,
+    pub standard: Standard,
+    pub mode: Mode,
     pub screen_width: u8,
     pub screen_height: u8,
 
     pub attributes: Attributes,
     pub page_mode: PageMode,
+    /// Keyboard letters are sent as lowercase (p143)
+    pub lowercase: bool,
+    /// Error correction procedure enabled (p143)
+    pub pce: bool,
     pub cursor_x: u8,
     pub cursor_y: u8,
     pub visible_cursor: bool,
     pub ignore_sequences: bool,
     pub screen_mask: bool,
     pub insert: bool,
+    pub scroll_region: (u8, u8),
 
     pub grid: Grid,
+    pub drcs: Drcs,
+    /// Connections between the modules of the terminal
+    pub routing: Routing,
+    /// Answer to the ROM identification request
+    pub identification: Identification,
     pub pending_attributes: PendingAttributes,
     saved_state_for_row_zero: Option<SavedState>,
+    saved_cursor: Option<SavedState>,
 
     response: Vec<u8>,
     beep: bool,
@@ -1316,22 +1697,31 @@ impl Context {
     pub fn new(display_component: DisplayComponent) -> Self {
         Self {
             display_component,
-            screen_width: 40,
+            standard: Standard::Teletel,
+            mode: Mode::Videotex,
+            screen_width: Mode::Videotex.screen_width(),
             screen_height: 24,
 
             //defaults are documented page 87 and 88
             attributes: Attributes::default(),
             page_mode: PageMode::Page,
+            lowercase: false,
+            pce: false,
             cursor_x: 1,
             cursor_y: 1,
             visible_cursor: false,
             ignore_sequences: false,
             screen_mask: true,
             insert: false,
+            scroll_region: (1, 24),
 
             grid: Grid::new(40, 24),
+            drcs: Drcs::default(),
+            routing: Routing::default(),
+            identification: Identification::default(),
             pending_attributes: PendingAttributes::default(),
             saved_state_for_row_zero: None,
+            saved_cursor: None,
 
             response: Vec::new(),
             beep: false,
@@ -1348,8 +1738,14 @@ impl Context {
             return Ok(());
         }
 
+        if !matches!(sequence, Sequence::Incomplete | Sequence::Drcs(_)) {
+            self.stop_drcs_loading();
+        }
+
         match sequence {
             Sequence::Incomplete => {}
+            Sequence::Ansi(ansi) => self.consume_ansi(ansi)?,
+            Sequence::Drcs(drcs) => self.consume_drcs(drcs)?,
             Sequence::Escaped(esc) => match esc {
                 EscapedSequence::Background(color) => {
                     self.pending_attributes.set_background(*color)
@@ -1409,7 +1805,7 @@ impl Context {
                     }
                     Csi::StartInsert => self.insert = true,
                     Csi::EndInsert => self.insert = false,
-                    _ => err!("Received incomplete CSI sequence {:?}", csi),
+                    _ => err!(Incomplete, "Received incomplete CSI sequence {:?}", csi),
                 },
                 EscapedSequence::NormalSize
                 | EscapedSequence::DoubleSize
@@ -1418,7 +1814,10 @@ impl Context {
                 | EscapedSequence::Invert(_)
                     if self.attributes.character_set == CharacterSet::G1 =>
                 {
-                    err!("Changing invert and character size while in G1 is not supported");
+                    err!(
+                        Unsupported,
+                        "Changing invert and character size while in G1 is not supported"
+                    );
                 }
                 EscapedSequence::Blink(blink) => self.attributes.blinking = *blink,
                 EscapedSequence::Invert(invert) => self.attributes.invert = *invert,
@@ -1426,10 +1825,23 @@ impl Context {
                     self.attributes.double_height = false;
                     self.attributes.double_width = false;
                 }
+                EscapedSequence::DoubleHeight
+                | EscapedSequence::DoubleWidth
+                | EscapedSequence::DoubleSize
+                    if self.mode == Mode::Mixte =>
+                {
+                    err!(
+                        Unsupported,
+                        "Double height, width or size are not supported in mixte mode"
+                    );
+                }
                 EscapedSequence::DoubleHeight | EscapedSequence::DoubleSize
                     if self.cursor_y <= 1 =>
                 {
-                    err!("Tried to set double height or double size while in row 0 or 1");
+                    err!(
+                        Unsupported,
+                        "Tried to set double height or double size while in row 0 or 1"
+                    );
                 }
                 EscapedSequence::DoubleHeight => self.attributes.double_height = true,
                 EscapedSequence::DoubleWidth => self.attributes.double_width = true,
@@ -1444,9 +1856,22 @@ impl Context {
                 EscapedSequence::Ignore(Some(ignore)) => self.ignore_sequences = *ignore,
                 EscapedSequence::Protocol(pro) => match pro {
                     //todo: impl missing sequences
-                    Protocol::Reset => *self = Self::new(self.display_component),
+                    Protocol::Reset => {
+                        let identification = self.identification;
+                        *self = Self::new(self.display_component);
+                        self.identification = identification;
+                    }
                     Protocol::RequestSpeed => {}
+                    Protocol::RequestStatus => {
+                        self.response
+                            .extend([ESC, PRO2, RESP_STATUS, self.status()]);
+                    }
+                    Protocol::RequestIdentification => {
+                        self.response.extend(self.identification.to_bytes());
+                    }
                     Protocol::SetSpeed(_) => {}
+                    Protocol::SwitchMode(Some(mode)) => self.switch_mode(*mode)?,
+                    Protocol::SwitchStandard(Some(standard)) => self.switch_standard(*standard)?,
                     //todo: not actually parse this but the response
                     Protocol::Scroll(scroll) => {
                         if *scroll {
@@ -1455,8 +1880,25 @@ impl Context {
                             self.page_mode = PageMode::Page;
                         }
                     }
+                    //the terminal answers with its functioning status (p143)
+                    Protocol::Procedure(pce) => {
+                        self.pce = *pce;
+                        self.response
+                            .extend([ESC, PRO2, RESP_STATUS, self.status()]);
+                    }
                     Protocol::Sleep(_) => {}
-                    _ => err!("Received incomplete protocol sequence {:?}", pro),
+                    Protocol::Route(on, Some(to), Some(from)) => {
+                        self.routing.set(*from, *to, *on);
+                        self.response.extend(self.routing.to_bytes(*to));
+                    }
+                    Protocol::RequestRouting(Some(to)) => {
+                        self.response.extend(self.routing.to_bytes(*to));
+                    }
+                    _ => err!(
+                        Incomplete,
+                        "Received incomplete protocol sequence {:?}",
+                        pro
+                    ),
                 },
                 EscapedSequence::GetCursorPosition => {
                     self.response.push(US);
@@ -1464,8 +1906,15 @@ impl Context {
                     self.response.push(0x40 + self.cursor_x);
                 }
                 EscapedSequence::ScreenMasking(mask) => self.screen_mask = *mask,
-                _ => err!("Received incomplete escaped sequence {:?}", esc),
+                EscapedSequence::Designation(set, drcs) => self.designate(*set, *drcs),
+                _ => err!(Incomplete, "Received incomplete escaped sequence {:?}", esc),
             },
+            Sequence::SetCharacterSet(CharacterSet::G1) if self.mode == Mode::Mixte => {
+                err!(
+                    Unsupported,
+                    "Semi-graphic characters are not supported in mixte mode"
+                );
+            }
             Sequence::SetCharacterSet(set) => {
                 self.attributes.character_set = *set;
                 self.attributes.underline = false; //p94 todo: on peut supprimer?
@@ -1521,14 +1970,20 @@ impl Context {
                     .map(|cell| cell.content)
                 {
                     if previous_char == '\0' {
-                        err!("Tried to repeat a character but no character was present");
+                        err!(
+                            NothingToRepeat,
+                            "Tried to repeat a character but no character was present"
+                        );
                     }
 
                     for _ in 0..value.unwrap() {
                         self.print(previous_char)?;
                     }
                 } else {
-                    err!("Tried to repeat a character at the beginning of the screen");
+                    err!(
+                        NothingToRepeat,
+                        "Tried to repeat a character at the beginning of the screen"
+                    );
                 }
             }
             Sequence::VisibleCursor(value) => self.visible_cursor = *value,
@@ -1539,11 +1994,99 @@ impl Context {
         Ok(())
     }
 
+    /// Returns the functioning status byte as it would be sent back by the
+    /// terminal in response to a status request (p143)
+    pub fn status(&self) -> u8 {
+        Status {
+            lowercase: self.lowercase,
+            pce: self.pce,
+            page_mode: self.page_mode,
+            eighty_columns: self.screen_width == 80,
+        }
+        .to_byte()
+    }
+
+    /// Whether the keys typed on the keyboard are displayed on the screen
+    /// by the terminal itself
+    pub fn local_echo(&self) -> bool {
+        self.routing.is_connected(Module::Keyboard, Module::Screen)
+    }
+
+    /// Follows the functioning status reported by the terminal. The screen
+    /// is resized and cleared if the width does not match, switching
+    /// between the videotex and mixte modes in the teletel standard.
+    fn apply_status(&mut self, status: Status) {
+        self.lowercase = status.lowercase;
+        self.pce = status.pce;
+        self.page_mode = status.page_mode;
+
+        let width = if status.eighty_columns { 80 } else { 40 };
+        if self.screen_width != width {
+            if self.standard == Standard::Teletel {
+                self.mode = if status.eighty_columns {
+                    Mode::Mixte
+                } else {
+                    Mode::Videotex
+                };
+            }
+
+            self.set_columns(width);
+        }
+    }
+
+    /// Switches between the videotex and mixte modes, the screen is resized
+    /// and cleared and the terminal acknowledges the switch (p144)
+    fn switch_mode(&mut self, mode: Mode) -> Result<(), Error> {
+        self.mode = mode;
+        self.screen_width = mode.screen_width();
+        self.grid = Grid::new(self.screen_width, self.screen_height);
+        self.reset_screen();
+        self.set_cursor(1, 1, false)?;
+
+        self.response.push(SEP);
+        self.response.push(match mode {
+            Mode::Videotex => VIDEOTEX_ACK,
+            Mode::Mixte => MIXED_ACK,
+        });
+
+        Ok(())
+    }
+
+    /// Switches between the teletel and téléinformatique standards. The
+    /// téléinformatique standard starts with an 80 columns screen. The screen
+    /// is cleared and the terminal acknowledges the switch (p144)
+    fn switch_standard(&mut self, standard: Standard) -> Result<(), Error> {
+        self.standard = standard;
+        self.mode = Mode::Videotex;
+        self.screen_width = match standard {
+            Standard::Teletel => Mode::Videotex.screen_width(),
+            Standard::Teleinformatique => 80,
+        };
+        self.grid = Grid::new(self.screen_width, self.screen_height);
+        self.scroll_region = (1, self.screen_height);
+        self.saved_cursor = None;
+        self.insert = false;
+        self.reset_screen();
+        self.set_cursor(1, 1, false)?;
+
+        match standard {
+            Standard::Teletel => self.response.extend(TELETEL_ACK),
+            Standard::Teleinformatique => self.response.extend(TELEINFORMATIQUE_ACK),
+        }
+
+        Ok(())
+    }
+
     fn set_cursor(&mut self, x: u8, y: u8, allow_row_zero: bool) -> Result<(), Error> {
         let minimum_y = if allow_row_zero { 0 } else { 1 };
 
         if x > self.screen_width || x < 1 || y > self.screen_height || y < minimum_y {
-            err!("Tried to move cursor outside of screen ({}, {})", x, y);
+            err!(
+                OutOfScreen,
+                "Tried to move cursor outside of screen ({}, {})",
+                x,
+                y
+            );
         }
 
         self.cursor_x = x;
@@ -1565,12 +2108,14 @@ impl Context {
     /// This function does not allow moving in row 0 (not yet implemented, p97)
     fn move_cursor_x(&mut self, x: i8, wrap: bool) -> Result<(), Error> {
         if self.cursor_y == 0 {
-            self.cursor_x = (self.cursor_x as i8 + x).clamp(1, self.screen_width as i8) as u8;
+            self.cursor_x =
+                (self.cursor_x as i16 + x as i16).clamp(1, self.screen_width as i16) as u8;
             return Ok(());
         }
 
         if !wrap {
-            self.cursor_x = (self.cursor_x as i8 + x).clamp(1, self.screen_width as i8) as u8;
+            self.cursor_x =
+                (self.cursor_x as i16 + x as i16).clamp(1, self.screen_width as i16) as u8;
             return Ok(());
         }
 
@@ -1666,6 +2211,9 @@ impl Context {
             .attributes
             .apply_pending(character, &mut self.pending_attributes);
 
+        //cells reference the redefined set if it was designated when printed
+        self.attributes.drcs = self.drcs.is_designated(self.attributes.character_set);
+
         // if self.attributes.double_width && self.cursor_x == self.screen_width {
         //     self.move_cursor_x(1, true);
         // }
@@ -1726,21 +2274,129 @@ impl Context {
 
 pub struct Parser {
     ctx: Context,
+    profile: TerminalProfile,
     sequence: Sequence,
+    offset: usize,
+    sequence_start: usize,
+    sequence_bytes: Vec<u8>,
+    blocks: BlockReader,
+    policy: Policy,
+    diagnostics: Box<dyn Diagnostics + Send + Sync>,
+    collected: Vec<Error>,
 }
 
 impl Parser {
     pub fn new(display_component: DisplayComponent) -> Self {
+        Self::from_profile(TerminalProfile {
+            display_component,
+            ..TerminalProfile::default()
+        })
+    }
+
+    pub fn from_profile(profile: TerminalProfile) -> Self {
+        let mut ctx = Context::new(profile.display_component);
+        ctx.identification.model = profile.model;
+
         Self {
-            ctx: Context::new(display_component),
+            ctx,
+            profile,
             sequence: Sequence::Incomplete,
+            offset: 0,
+            sequence_start: 0,
+            sequence_bytes: Vec::new(),
+            blocks: BlockReader::new(),
+            policy: Policy::default(),
+            #[cfg(feature = "std")]
+            diagnostics: Box::new(Stderr),
+            #[cfg(not(feature = "std"))]
+            diagnostics: Box::new(|_: &Error| {}),
+            collected: Vec::new(),
         }
     }
 
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    /// Replaces the sink receiving the errors recovered with the lenient
+    /// policy, they are written to the standard error output by default
+    /// and discarded without the `std` feature.
+    pub fn set_diagnostics<D: Diagnostics + Send + Sync + 'static>(&mut self, diagnostics: D) {
+        self.diagnostics = Box::new(diagnostics);
+    }
+
+    /// Returns and clears the errors recovered with the collect policy.
+    pub fn take_diagnostics(&mut self) -> Vec<Error> {
+        mem::take(&mut self.collected)
+    }
+
     pub fn ctx(&self) -> &Context {
         &self.ctx
     }
 
+    pub fn profile(&self) -> &TerminalProfile {
+        &self.profile
+    }
+
+    /// Changes the terminal the parser validates sequences against, the
+    /// screen is kept but characters are now displayed with the display
+    /// component of the new profile.
+    pub fn set_profile(&mut self, profile: TerminalProfile) {
+        self.ctx.display_component = profile.display_component;
+        self.ctx.identification.model = profile.model;
+        self.profile = profile;
+    }
+
+    /// Changes the answer to the ROM identification request
+    pub fn set_identification(&mut self, identification: Identification) {
+        self.ctx.identification = identification;
+    }
+
+    /// Forces the standard followed by the parser without going through
+    /// the switch sequence, useful when the terminal is known to already
+    /// be in a given standard. The acknowledgement is not emitted.
+    pub fn set_standard(&mut self, standard: Standard) {
+        let pending_response = self.ctx.response.len();
+
+        self.sequence = Sequence::Incomplete;
+        //switching standard always succeeds as the cursor is moved to (1, 1)
+        let _ = self.ctx.switch_standard(standard);
+        self.ctx.response.truncate(pending_response);
+    }
+
+    /// Updates the context from a response read from the terminal so that
+    /// it follows the state the terminal reports. Only the functioning
+    /// status, routing and cursor position responses are understood, other
+    /// responses are ignored.
+    pub fn observe(&mut self, response: &[u8]) {
+        match response {
+            [ESC, PRO2, RESP_STATUS, status] => {
+                self.ctx.apply_status(Status::from_byte(*status));
+            }
+            [ESC, PRO3, ROUTING_RESPONSE, receiver, status] => {
+                if let Some(to) = Module::from_receiver_code(*receiver) {
+                    self.ctx.routing.set_status(to, *status);
+                }
+            }
+            [US, row, column] => {
+                //a position outside of the screen is ignored
+                let _ =
+                    self.ctx
+                        .set_cursor(column.wrapping_sub(0x40), row.wrapping_sub(0x40), true);
+            }
+            _ => {}
+        }
+    }
+
     /// Returns and clears any pending protocol response bytes.
     pub fn take_response(&mut self) -> Vec<u8> {
         mem::take(&mut self.ctx.response)
@@ -1757,46 +2413,99 @@ impl Parser {
         mem::replace(&mut self.ctx.beep, false)
     }
 
+    /// Consumes a byte. While the error correction procedure is enabled,
+    /// bytes are grouped in blocks that are only parsed once verified and
+    /// corrupted blocks are reported in the response (p146).
     pub fn consume(&mut self, byte: u8) -> Result<(), Error> {
-        let mut result = || {
-            self.sequence = self.sequence.consume(&self.ctx, byte)?;
+        if !self.ctx.pce {
+            self.blocks.reset();
+            return self.consume_event(byte).map(|_| ());
+        }
 
-            if self.sequence.is_complete() {
-                let complete_sequence = mem::replace(&mut self.sequence, Sequence::Incomplete);
-                self.ctx.consume(&complete_sequence)?;
+        match self.blocks.push(byte) {
+            Some(Block::Data(data)) => data
+                .iter()
+                //NUL bytes pad the blocks and may appear inside a sequence
+                .filter(|byte| **byte != 0x00)
+                .try_for_each(|byte| self.consume_event(*byte).map(|_| ())),
+            Some(Block::Corrupted(number)) => {
+                self.ctx.response.extend([NACK, BLOCK_NUMBER + number]);
+                Ok(())
             }
+            None => Ok(()),
+        }
+    }
 
-            Ok(())
+    /// Consumes a byte and returns the event of the sequence it completed,
+    /// bypassing the error correction procedure
+    pub fn consume_event(&mut self, byte: u8) -> Result<Option<Event>, Error> {
+        let result = match self.step(byte) {
+            Err(_) if self.sequence != Sequence::Incomplete && byte < 0x20 && byte != 0x00 => {
+                self.sequence = Sequence::Incomplete;
+                self.step(byte)
+            }
+            result => result,
         };
 
-        if cfg!(feature = "strict") {
-            result().or_else(|err| {
-                if self.sequence != Sequence::Incomplete && byte < 0x20 && byte != 0x00 {
-                    self.sequence = Sequence::Incomplete;
-                    self.consume(byte)
-                } else {
-                    Err(err)
-                }
-            })
-        } else {
-            if let Err(err) = result() {
-                if self.sequence != Sequence::Incomplete && byte < 0x20 && byte != 0x00 {
-                    self.sequence = Sequence::Incomplete;
-                    return self.consume(byte);
-                }
+        let result = result.map_err(|err| err.at(byte, self.offset, &self.sequence_bytes));
+        self.offset += 1;
 
-                //todo: use logging library
-                eprintln!("{}", err);
+        match (result, self.policy) {
+            (Err(err), Policy::Lenient) => {
+                self.diagnostics.warning(&err);
+                Ok(None)
+            }
+            (Err(err), Policy::Collect) => {
+                self.collected.push(err);
+                Ok(None)
             }
+            (result, _) => result,
+        }
+    }
+
+    /// Returns an iterator consuming the given bytes and yielding the
+    /// events of the sequences they complete
+    pub fn consume_events<I: IntoIterator>(&mut self, bytes: I) -> Events<'_, I::IntoIter>
+    where
+        I::Item: Borrow<u8>,
+    {
+        Events::new(self, bytes.into_iter())
+    }
+
+    fn step(&mut self, byte: u8) -> Result<Option<Event>, Error> {
+        if self.sequence == Sequence::Incomplete {
+            self.sequence_start = self.offset;
+            self.sequence_bytes.clear();
+        }
+
+        self.sequence_bytes.push(byte);
+
+        self.sequence = self.sequence.consume(&self.ctx, byte)?;
+
+        if !self.sequence.is_complete() {
+            return Ok(None);
+        }
+
+        let complete_sequence = mem::replace(&mut self.sequence, Sequence::Incomplete);
+        let event = complete_sequence.to_event().map(|kind| Event {
+            kind,
+            span: self.sequence_start..self.offset + 1,
+        });
 
-            Ok(())
+        if let Some(event) = &event {
+            self.profile.validate(&event.kind)?;
         }
+
+        self.ctx.consume(&complete_sequence)?;
+
+        Ok(event)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_character_set() {
@@ -1820,6 +2529,7 @@ mod tests {
         assert_eq!(
             CharacterSet::new(&ctx, SI).unwrap().consume(&ctx, 0x00),
             Err(Error::new(
+                ErrorKind::InvalidSequence,
                 "Character set G0 does not support more bytes (0x00)"
             )),
         );
@@ -1827,6 +2537,7 @@ mod tests {
         assert_eq!(
             CharacterSet::new(&ctx, SI).unwrap().consume(&ctx, 0x0E),
             Err(Error::new(
+                ErrorKind::InvalidSequence,
                 "Character set G0 does not support more bytes (0x0E)"
             )),
         );
@@ -1834,6 +2545,7 @@ mod tests {
         assert_eq!(
             CharacterSet::new(&ctx, SO).unwrap().consume(&ctx, 0x00),
             Err(Error::new(
+                ErrorKind::InvalidSequence,
                 "Character set G1 does not support more bytes (0x00)"
             )),
         );
@@ -1841,6 +2553,7 @@ mod tests {
         assert_eq!(
             CharacterSet::new(&ctx, SO).unwrap().consume(&ctx, 0x0F),
             Err(Error::new(
+                ErrorKind::InvalidSequence,
                 "Character set G1 does not support more bytes (0x0F)"
             )),
         );
@@ -2572,6 +3285,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_grid_shifts_rows() {
+        let mut grid = Grid::new(40, 24);
+        grid.cell_mut(1, 1).set_content('A');
+        grid.cell_mut(1, 2).set_content('B');
+        grid.cell_mut(1, 24).set_content('Z');
+
+        grid.insert_rows_after(1, 1);
+        assert_eq!(grid.cell(1, 1).content, 'A');
+        assert_eq!(grid.cell(1, 2).content, ' ');
+        assert_eq!(grid.cell(1, 3).content, 'B');
+        assert_eq!(grid.cell(1, 24).content, ' ');
+
+        grid.delete_rows_after(1, 1);
+        assert_eq!(grid.cell(1, 2).content, 'B');
+        assert_eq!(grid.cell(1, 24).content, ' ');
+
+        grid.scroll(1);
+        assert_eq!(grid.cell(1, 1).content, 'B');
+        assert_eq!(grid.cell(1, 2).content, ' ');
+
+        grid.scroll(-2);
+        assert_eq!(grid.cell(1, 1).content, ' ');
+        assert_eq!(grid.cell(1, 3).content, 'B');
+    }
+
     #[test]
     fn test_move_cursor_y_wraps_in_page_mode() {
         let mut ctx = Context::new(DisplayComponent::VGP2);
@@ -2697,6 +3436,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_kinds() {
+        let mut ctx = Context::new(DisplayComponent::VGP2);
+
+        let err = ctx.consume(&Sequence::Repeat(Some(3))).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NothingToRepeat);
+        assert_eq!(err.byte(), None);
+        assert_eq!(err.offset(), None);
+        assert!(err.sequence().is_empty());
+
+        ctx.attributes.character_set = CharacterSet::G1;
+        let err = SimpleCharacter::new(&ctx, 0x20).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidCharacter);
+        assert_eq!(err.message(), "Invalid simple character 0x20");
+
+        ctx.attributes.character_set = CharacterSet::G0;
+        ctx.cursor_y = 1;
+        let err = ctx
+            .consume(&Sequence::Escaped(EscapedSequence::DoubleHeight))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+    }
+
     #[cfg(not(feature = "strict"))]
     #[test]
     fn test_parser_non_strict_logs_and_continues_after_semantic_error() {
@@ -2726,6 +3488,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parser_strict_error_is_positioned() {
+        let mut parser = Parser::new(DisplayComponent::VGP2).with_policy(Policy::Strict);
+
+        assert_eq!(parser.consume(b'A'), Ok(()));
+        assert_eq!(parser.consume(b'B'), Ok(()));
+        assert_eq!(parser.consume(ESC), Ok(()));
+        assert_eq!(parser.consume(CSI), Ok(()));
+        assert_eq!(parser.consume(0x31), Ok(()));
+
+        let err = parser.consume(0x7F).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidSequence);
+        assert_eq!(err.byte(), Some(0x7F));
+        assert_eq!(err.offset(), Some(5));
+        assert_eq!(err.sequence(), [ESC, CSI, 0x31, 0x7F]);
+    }
+
+    #[test]
+    fn test_parser_lenient_policy_reports_to_sink() {
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = warnings.clone();
+
+        let mut parser = Parser::new(DisplayComponent::VGP2).with_policy(Policy::Lenient);
+        parser.set_diagnostics(move |err: &Error| sink.lock().unwrap().push(err.clone()));
+
+        assert_eq!(parser.consume(b'A'), Ok(()));
+        assert_eq!(parser.consume(REP), Ok(()));
+        assert_eq!(parser.consume(0x20), Ok(()));
+        assert_eq!(parser.consume(b'B'), Ok(()));
+
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind(), ErrorKind::InvalidSequence);
+        assert_eq!(warnings[0].offset(), Some(2));
+        assert_eq!(warnings[0].sequence(), [REP, 0x20]);
+        assert!(parser.take_diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_parser_collect_policy_stores_errors() {
+        let mut parser = Parser::new(DisplayComponent::VGP2).with_policy(Policy::Collect);
+        parser.set_diagnostics(|_: &Error| panic!("collected errors are not reported"));
+
+        assert_eq!(parser.consume(b'A'), Ok(()));
+        assert_eq!(parser.consume(REP), Ok(()));
+        assert_eq!(parser.consume(0x20), Ok(()));
+        assert_eq!(parser.consume(CR), Ok(()));
+        assert_eq!(parser.consume(REP), Ok(()));
+        assert_eq!(parser.consume(0x21), Ok(()));
+        assert_eq!(parser.consume(CR), Ok(()));
+
+        let errors = parser.take_diagnostics();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].offset(), Some(2));
+        assert_eq!(errors[1].offset(), Some(5));
+        assert!(parser.take_diagnostics().is_empty());
+        assert_eq!(parser.ctx().grid.cell(1, 1).content, 'A');
+    }
+
     #[test]
     fn test_get_cursor_position_emits_response() {
         let mut parser = Parser::new(DisplayComponent::VGP2);
@@ -2754,6 +3575,18 @@ mod tests {
         assert!(!parser.has_response());
     }
 
+    #[test]
+    fn test_observed_cursor_position_moves_cursor() {
+        let mut parser = Parser::new(DisplayComponent::VGP2);
+
+        parser.observe(&[US, 0x40 + 5, 0x40 + 10]);
+        assert_eq!(parser.ctx().cursor_y, 5);
+        assert_eq!(parser.ctx().cursor_x, 10);
+
+        parser.observe(&[US, 0x40 + 30, 0x40 + 10]);
+        assert_eq!(parser.ctx().cursor_y, 5);
+    }
+
     #[test]
     fn test_beep_sets_flag() {
         let mut parser = Parser::new(DisplayComponent::VGP2);
@@ -2784,4 +3617,128 @@ mod tests {
         assert_eq!(parser.ctx().grid.cell(1, 1).content, '\u{7F}');
         assert_eq!(parser.ctx().cursor_x, 2);
     }
+
+    #[test]
+    fn test_switch_to_mixte_mode() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        parser.consume(b'A').unwrap();
+
+        for byte in [ESC, PRO2, MIXED, TO_MIXED] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(parser.ctx().mode, Mode::Mixte);
+        assert_eq!(parser.ctx().screen_width, 80);
+        assert_eq!(parser.ctx().cursor_x, 1);
+        assert_eq!(parser.ctx().cursor_y, 1);
+        assert!(parser.ctx().grid.cell(1, 1).is_delimiter);
+        assert!(parser.ctx().grid.cell_opt(80, 24).is_some());
+        assert!(parser.ctx().grid.cell_opt(81, 24).is_none());
+        assert_eq!(parser.take_response(), vec![SEP, MIXED_ACK]);
+        assert_eq!(parser.ctx().status() & EIGHTY_COLUMNS, EIGHTY_COLUMNS);
+
+        for byte in [ESC, PRO2, MIXED, TO_VIDEOTEX] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(parser.ctx().mode, Mode::Videotex);
+        assert_eq!(parser.ctx().screen_width, 40);
+        assert!(parser.ctx().grid.cell_opt(41, 24).is_none());
+        assert_eq!(parser.take_response(), vec![SEP, VIDEOTEX_ACK]);
+        assert_eq!(parser.ctx().status() & EIGHTY_COLUMNS, 0);
+    }
+
+    #[test]
+    fn test_mixte_mode_wraps_at_80_columns() {
+        let mut ctx = Context::new(DisplayComponent::VGP5);
+        ctx.switch_mode(Mode::Mixte).unwrap();
+
+        ctx.set_cursor(79, 3, false).unwrap();
+        ctx.print('A').unwrap();
+        ctx.print('B').unwrap();
+
+        assert_eq!(ctx.grid.cell(79, 3).content, 'A');
+        assert_eq!(ctx.grid.cell(80, 3).content, 'B');
+        assert_eq!(ctx.cursor_x, 1);
+        assert_eq!(ctx.cursor_y, 4);
+
+        assert_eq!(ctx.set_cursor(80, 24, false), Ok(()));
+        assert_err!(
+            ctx.set_cursor(81, 24, false),
+            "Tried to move cursor outside of screen (81, 24)"
+        );
+    }
+
+    #[test]
+    fn test_mixte_mode_accepts_80_columns_set_cursor() {
+        let mut ctx = Context::new(DisplayComponent::VGP5);
+        ctx.switch_mode(Mode::Mixte).unwrap();
+
+        let set_cursor_75_2 = Csi::new(&ctx, 0x5B)
+            .unwrap()
+            .consume(&ctx, 0x30)
+            .unwrap()
+            .consume(&ctx, 0x32)
+            .unwrap()
+            .consume(&ctx, 0x3B)
+            .unwrap()
+            .consume(&ctx, 0x37)
+            .unwrap()
+            .consume(&ctx, 0x35)
+            .unwrap()
+            .consume(&ctx, 0x48)
+            .unwrap();
+
+        assert_eq!(set_cursor_75_2, Csi::SetCursor(75, 2));
+    }
+
+    #[test]
+    fn test_mixte_mode_is_g0_only() {
+        let mut ctx = Context::new(DisplayComponent::VGP5);
+        ctx.switch_mode(Mode::Mixte).unwrap();
+        ctx.set_cursor(1, 5, false).unwrap();
+
+        assert_err!(
+            ctx.consume(&Sequence::SetCharacterSet(CharacterSet::G1)),
+            "Semi-graphic characters are not supported in mixte mode"
+        );
+        assert_err!(
+            SpecialCharacter::new(&ctx, SS2),
+            "Special characters are not supported in mixte mode"
+        );
+        assert_err!(
+            ctx.consume(&Sequence::Escaped(EscapedSequence::DoubleSize)),
+            "Double height, width or size are not supported in mixte mode"
+        );
+        assert_err!(
+            ctx.consume(&Sequence::Escaped(EscapedSequence::DoubleWidth)),
+            "Double height, width or size are not supported in mixte mode"
+        );
+    }
+
+    #[test]
+    fn test_reset_leaves_mixte_mode() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        for byte in [ESC, PRO2, MIXED, TO_MIXED, ESC, PRO1, RESET] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(parser.ctx().mode, Mode::Videotex);
+        assert_eq!(parser.ctx().screen_width, 40);
+    }
+
+    #[test]
+    fn test_status_request_emits_response() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        for byte in [ESC, PRO2, START, SCROLL, ESC, PRO1, REQ_STATUS] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(
+            parser.take_response(),
+            vec![ESC, PRO2, RESP_STATUS, STATUS_BYTE | PAGE_MODE]
+        );
+    }
 }
@@ -0,0 +1,287 @@
+use super::*;
+use crate::codes::pce::*;
+
+/// Block of the error correction procedure as received by the terminal
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Block {
+    /// Data of a valid block, padded with NUL bytes
+    Data([u8; Block::LENGTH]),
+    /// Number of the block that was corrupted, the terminal answers
+    /// `NACK <0x40 + number>` and waits for the host to send it again
+    Corrupted(u8),
+}
+
+impl Block {
+    /// Number of data bytes in a block
+    pub const LENGTH: usize = 15;
+
+    /// Number of bytes of a block once framed
+    pub const FRAMED_LENGTH: usize = Block::LENGTH + 2;
+
+    /// Check byte of the data of a block, computed on its 120 bits with
+    /// the `x^7 + x^3 + 1` polynomial
+    pub fn checksum(data: &[u8]) -> u8 {
+        let mut checksum = 0;
+
+        for byte in data {
+            for bit in (0..8).rev() {
+                let carry = (checksum >> 6) & 1 != (byte >> bit) & 1;
+
+                checksum = (checksum << 1) & 0x7F;
+                if carry {
+                    checksum ^= POLYNOMIAL;
+                }
+            }
+        }
+
+        checksum
+    }
+
+    /// Frames up to 15 bytes of data, missing bytes are padded with NUL
+    pub fn frame(data: &[u8]) -> [u8; Block::FRAMED_LENGTH] {
+        assert!(
+            data.len() <= Block::LENGTH,
+            "A block holds at most 15 bytes"
+        );
+
+        let mut block = [0; Block::FRAMED_LENGTH];
+        block[..data.len()].copy_from_slice(data);
+        block[Block::LENGTH] = Block::checksum(&block[..Block::LENGTH]);
+
+        block
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum State {
+    Receiving,
+    /// Reading `SYN SYN <block number>` sent before blocks are sent again,
+    /// with the number of SYN received so far. After a corrupted block,
+    /// everything else is ignored until it is read.
+    Resynchronizing(u8),
+}
+
+/// Verifies the blocks received while the error correction procedure is
+/// enabled, as done by the terminal.
+///
+/// The blocks sent again that were already received are skipped, so that
+/// a reader fed with everything the host sends, such as the parser of the
+/// host itself, sees every block once.
+#[derive(Clone, Debug)]
+pub struct BlockReader {
+    buffer: Vec<u8>,
+    /// Number of the block being received
+    number: u8,
+    state: State,
+    /// Number of blocks sent again that were already received
+    skip: u8,
+}
+
+impl BlockReader {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::with_capacity(Block::FRAMED_LENGTH),
+            number: 0,
+            state: State::Receiving,
+            skip: 0,
+        }
+    }
+
+    /// Forgets the block being received, the next block is numbered 0
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.number = 0;
+        self.state = State::Receiving;
+        self.skip = 0;
+    }
+
+    /// Pushes a received byte and returns the block it completed
+    pub fn push(&mut self, byte: u8) -> Option<Block> {
+        match self.state {
+            //SYN is reserved to the procedure, blocks never start with it
+            State::Receiving if self.buffer.is_empty() && byte == SYN => {
+                self.state = State::Resynchronizing(1);
+                return None;
+            }
+            State::Receiving => {}
+            State::Resynchronizing(count) => {
+                self.state = match byte {
+                    SYN => State::Resynchronizing(count.saturating_add(1)),
+                    _ if count >= 2 && (BLOCK_NUMBER..BLOCK_NUMBER + 16).contains(&byte) => {
+                        //blocks are only sent again starting from an older one
+                        self.skip = (self.number + 16 - (byte - BLOCK_NUMBER)) % 16;
+                        State::Receiving
+                    }
+                    _ => State::Resynchronizing(0),
+                };
+
+                return None;
+            }
+        }
+
+        self.buffer.push(byte);
+        if self.buffer.len() < Block::FRAMED_LENGTH {
+            return None;
+        }
+
+        if self.skip > 0 {
+            self.skip -= 1;
+            self.buffer.clear();
+            return None;
+        }
+
+        let data = &self.buffer[..Block::LENGTH];
+        let valid = Block::checksum(data) == self.buffer[Block::LENGTH]
+            && self.buffer[Block::LENGTH + 1] == 0;
+
+        let block = if valid {
+            let mut block = [0; Block::LENGTH];
+            block.copy_from_slice(data);
+
+            self.number = (self.number + 1) % 16;
+            Block::Data(block)
+        } else {
+            self.state = State::Resynchronizing(0);
+            Block::Corrupted(self.number)
+        };
+
+        self.buffer.clear();
+
+        Some(block)
+    }
+}
+
+impl Default for BlockReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_all(reader: &mut BlockReader, bytes: &[u8]) -> Vec<Block> {
+        bytes.iter().filter_map(|byte| reader.push(*byte)).collect()
+    }
+
+    #[test]
+    fn test_frame() {
+        let block = Block::frame(b"hello");
+
+        assert_eq!(&block[..5], b"hello");
+        assert_eq!(&block[5..15], [0; 10]);
+        assert_eq!(block[15], Block::checksum(&block[..15]));
+        assert_eq!(block[16], 0);
+        assert!(block[15] < 0x80);
+        assert_eq!(Block::checksum(&[0; 15]), 0);
+    }
+
+    #[test]
+    fn test_single_bit_errors_are_detected() {
+        let block = Block::frame(b"Minitel 1B");
+
+        for bit in 0..Block::LENGTH * 8 {
+            let mut corrupted = block;
+            corrupted[bit / 8] ^= 1 << (bit % 8);
+
+            assert_ne!(
+                Block::checksum(&corrupted[..15]),
+                block[15],
+                "bit {} was not detected",
+                bit
+            );
+        }
+    }
+
+    #[test]
+    fn test_corrupted_block_is_sent_again() {
+        let mut reader = BlockReader::new();
+
+        let first = Block::frame(b"first");
+        let mut second = Block::frame(b"second");
+        assert_eq!(
+            push_all(&mut reader, &first),
+            [Block::Data(first[..15].try_into().unwrap())]
+        );
+
+        second[2] ^= 0x04;
+        assert_eq!(push_all(&mut reader, &second), [Block::Corrupted(1)]);
+
+        //everything is ignored until the host resynchronizes
+        second[2] ^= 0x04;
+        assert_eq!(push_all(&mut reader, &second), []);
+        assert_eq!(push_all(&mut reader, &[SYN, SYN, BLOCK_NUMBER]), []);
+        assert_eq!(push_all(&mut reader, &second), []);
+
+        assert_eq!(
+            push_all(&mut reader, &[SYN, SYN, SYN, BLOCK_NUMBER + 1]),
+            []
+        );
+        assert_eq!(
+            push_all(&mut reader, &second),
+            [Block::Data(second[..15].try_into().unwrap())]
+        );
+    }
+
+    #[test]
+    fn test_blocks_sent_again_are_read_once() {
+        let mut reader = BlockReader::new();
+
+        let blocks = [Block::frame(b"first"), Block::frame(b"second")];
+        for block in blocks {
+            assert_eq!(
+                push_all(&mut reader, &block),
+                [Block::Data(block[..15].try_into().unwrap())]
+            );
+        }
+
+        //the terminal asked for the second block again
+        assert_eq!(push_all(&mut reader, &[SYN, SYN, BLOCK_NUMBER + 1]), []);
+        assert_eq!(push_all(&mut reader, &blocks[1]), []);
+
+        let third = Block::frame(b"third");
+        assert_eq!(
+            push_all(&mut reader, &third),
+            [Block::Data(third[..15].try_into().unwrap())]
+        );
+    }
+
+    #[test]
+    fn test_parser_verifies_blocks() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        for byte in [ESC, PRO2, START, PROCEDURE] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert!(parser.ctx().pce);
+        assert_eq!(parser.take_response(), [ESC, PRO2, RESP_STATUS, STATUS_BYTE | PCE]);
+
+        let mut block = Block::frame(b"abc");
+        block[1] ^= 0x01;
+        for byte in block {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(parser.take_response(), [NACK, BLOCK_NUMBER]);
+        assert_eq!(parser.ctx().cursor_x, 1);
+
+        block[1] ^= 0x01;
+        for byte in [SYN, SYN, BLOCK_NUMBER].into_iter().chain(block) {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(parser.ctx().grid.cell(2, 1).content, 'b');
+        assert_eq!(parser.ctx().cursor_x, 4);
+
+        for byte in Block::frame(&[ESC, PRO2, STOP, PROCEDURE]) {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert!(!parser.ctx().pce);
+        assert_eq!(parser.take_response(), [ESC, PRO2, RESP_STATUS, STATUS_BYTE]);
+        assert_eq!(parser.consume(b'd'), Ok(()));
+        assert_eq!(parser.ctx().grid.cell(4, 1).content, 'd');
+    }
+}
@@ -0,0 +1,164 @@
+use super::*;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Model {
+    Minitel1,
+    Minitel1Color,
+    Minitel10,
+    Minitel1B,
+    Minitel10B,
+    Minitel2,
+    Minitel5,
+    Minitel12,
+    Terminatel252,
+    Printer,
+    Emulator,
+    Other(u8),
+}
+
+/// Capabilities that are not available on every model
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Feature {
+    /// Downloadable character sets (DRCS)
+    Drcs,
+    /// Putting the screen to sleep through PRO3 (p146)
+    Standby,
+}
+
+/// Describes the terminal on the other end of the connection, sequences
+/// that are not supported by the profile are refused by the parser.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct TerminalProfile {
+    pub model: Model,
+    pub display_component: DisplayComponent,
+    /// Supported connector speeds in bauds
+    pub speeds: &'static [u16],
+    pub features: &'static [Feature],
+}
+
+impl TerminalProfile {
+    pub const MINITEL_1B: TerminalProfile = TerminalProfile {
+        model: Model::Minitel1B,
+        display_component: DisplayComponent::VGP2,
+        speeds: &[300, 1200, 4800],
+        features: &[],
+    };
+
+    pub const MINITEL_2: TerminalProfile = TerminalProfile {
+        model: Model::Minitel2,
+        display_component: DisplayComponent::VGP5,
+        speeds: &[300, 1200, 4800, 9600],
+        features: &[Feature::Drcs, Feature::Standby],
+    };
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+
+    pub fn supports_speed(&self, speed: u16) -> bool {
+        self.speeds.contains(&speed)
+    }
+
+    /// Fastest speed supported by the terminal
+    pub fn max_speed(&self) -> u16 {
+        self.speeds.iter().copied().max().unwrap_or(1200)
+    }
+
+    /// Returns an error if the event can not be handled by the terminal
+    pub fn validate(&self, event: &EventKind) -> Result<(), Error> {
+        match event {
+            EventKind::DrcsDefinition(_)
+            | EventKind::DrcsStart(_)
+            | EventKind::DrcsGlyph(_)
+            | EventKind::Designation { drcs: true, .. }
+                if !self.supports(Feature::Drcs) =>
+            {
+                err!(
+                    Unsupported,
+                    "DRCS are not supported by the {:?}",
+                    self.model
+                )
+            }
+            EventKind::Sleep(_) if !self.supports(Feature::Standby) => {
+                err!(
+                    Unsupported,
+                    "Standby is not supported by the {:?}",
+                    self.model
+                )
+            }
+            EventKind::SetSpeed(code) => match speed(*code) {
+                Some(speed) if self.supports_speed(speed) => Ok(()),
+                _ => err!(
+                    Unsupported,
+                    "Speed {:#04X} is not supported by the {:?}",
+                    code,
+                    self.model
+                ),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for TerminalProfile {
+    fn default() -> Self {
+        TerminalProfile::MINITEL_2
+    }
+}
+
+/// Converts a speed code of the PRO2 programming sequence to bauds (p141)
+fn speed(code: u8) -> Option<u16> {
+    match code {
+        B300 => Some(300),
+        B1200 => Some(1200),
+        B4800 => Some(4800),
+        B9600 => Some(9600),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        assert!(TerminalProfile::MINITEL_2.supports(Feature::Drcs));
+        assert!(!TerminalProfile::MINITEL_1B.supports(Feature::Standby));
+        assert_eq!(TerminalProfile::MINITEL_1B.max_speed(), 4800);
+        assert_eq!(TerminalProfile::MINITEL_2.max_speed(), 9600);
+    }
+
+    #[test]
+    fn test_minitel_1b_refuses_minitel_2_sequences() {
+        let profile = TerminalProfile::MINITEL_1B;
+
+        assert_eq!(profile.validate(&EventKind::SetSpeed(B4800)), Ok(()));
+        assert_err!(
+            profile.validate(&EventKind::SetSpeed(B9600)),
+            "Speed 0x7F is not supported by the Minitel1B"
+        );
+        assert_err!(
+            profile.validate(&EventKind::Sleep(true)),
+            "Standby is not supported by the Minitel1B"
+        );
+        assert_err!(
+            profile.validate(&EventKind::DrcsStart(0x21)),
+            "DRCS are not supported by the Minitel1B"
+        );
+    }
+
+    #[test]
+    fn test_parser_validates_against_profile() {
+        let mut parser =
+            Parser::from_profile(TerminalProfile::MINITEL_1B).with_policy(Policy::Strict);
+
+        for byte in [ESC, PRO3, START, SCREEN] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        let err = parser.consume(0x41).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+        assert_eq!(err.sequence(), [ESC, PRO3, START, SCREEN, 0x41]);
+    }
+}
@@ -0,0 +1,234 @@
+use super::*;
+use crate::codes::routing::*;
+
+/// Internal modules of the terminal, each one can emit data to and receive
+/// data from the others depending on the routing
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Module {
+    Screen,
+    Keyboard,
+    Modem,
+    /// DIN socket on the back of the terminal (prise)
+    Socket,
+}
+
+impl Module {
+    pub const ALL: [Module; 4] = [
+        Module::Screen,
+        Module::Keyboard,
+        Module::Modem,
+        Module::Socket,
+    ];
+
+    pub fn from_receiver_code(code: u8) -> Option<Self> {
+        match code {
+            SCREEN_RECEIVER => Some(Module::Screen),
+            KEYBOARD_RECEIVER => Some(Module::Keyboard),
+            MODEM_RECEIVER => Some(Module::Modem),
+            SOCKET_RECEIVER => Some(Module::Socket),
+            _ => None,
+        }
+    }
+
+    pub fn from_emitter_code(code: u8) -> Option<Self> {
+        match code {
+            SCREEN_EMITTER => Some(Module::Screen),
+            KEYBOARD_EMITTER => Some(Module::Keyboard),
+            MODEM_EMITTER => Some(Module::Modem),
+            SOCKET_EMITTER => Some(Module::Socket),
+            _ => None,
+        }
+    }
+
+    pub fn receiver_code(&self) -> u8 {
+        match self {
+            Module::Screen => SCREEN_RECEIVER,
+            Module::Keyboard => KEYBOARD_RECEIVER,
+            Module::Modem => MODEM_RECEIVER,
+            Module::Socket => SOCKET_RECEIVER,
+        }
+    }
+
+    pub fn emitter_code(&self) -> u8 {
+        match self {
+            Module::Screen => SCREEN_EMITTER,
+            Module::Keyboard => KEYBOARD_EMITTER,
+            Module::Modem => MODEM_EMITTER,
+            Module::Socket => SOCKET_EMITTER,
+        }
+    }
+
+    /// Bit of the module in the routing status of a receiver
+    pub fn status_bit(&self) -> u8 {
+        match self {
+            Module::Screen => FROM_SCREEN,
+            Module::Keyboard => FROM_KEYBOARD,
+            Module::Modem => FROM_MODEM,
+            Module::Socket => FROM_SOCKET,
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Module::Screen => 0,
+            Module::Keyboard => 1,
+            Module::Modem => 2,
+            Module::Socket => 3,
+        }
+    }
+}
+
+/// Connections between the emitters and the receivers of the modules
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Routing {
+    /// Routing status of each receiver, one bit per connected emitter
+    receivers: [u8; 4],
+}
+
+impl Routing {
+    pub fn is_connected(&self, from: Module, to: Module) -> bool {
+        self.status(to) & from.status_bit() != 0
+    }
+
+    /// Modules emitting to the receiver
+    pub fn emitters(&self, to: Module) -> impl Iterator<Item = Module> + '_ {
+        Module::ALL
+            .into_iter()
+            .filter(move |from| self.is_connected(*from, to))
+    }
+
+    pub fn set(&mut self, from: Module, to: Module, on: bool) {
+        if on {
+            self.receivers[to.index()] |= from.status_bit();
+        } else {
+            self.receivers[to.index()] &= !from.status_bit();
+        }
+    }
+
+    /// Routing status of the receiver, one bit per connected emitter
+    pub fn status(&self, to: Module) -> u8 {
+        self.receivers[to.index()]
+    }
+
+    pub fn set_status(&mut self, to: Module, status: u8) {
+        self.receivers[to.index()] =
+            status & (FROM_SCREEN | FROM_KEYBOARD | FROM_MODEM | FROM_SOCKET);
+    }
+
+    /// Answer of the terminal to a routing command or request
+    pub fn to_bytes(&self, to: Module) -> [u8; 5] {
+        [
+            ESC,
+            PRO3,
+            ROUTING_RESPONSE,
+            to.receiver_code(),
+            STATUS_BYTE | self.status(to),
+        ]
+    }
+}
+
+impl Default for Routing {
+    /// The keyboard emits to the modem and is echoed on the screen, the
+    /// modem emits to the screen and both ways to the socket
+    fn default() -> Self {
+        let mut routing = Routing { receivers: [0; 4] };
+        routing.set(Module::Keyboard, Module::Screen, true);
+        routing.set(Module::Modem, Module::Screen, true);
+        routing.set(Module::Keyboard, Module::Modem, true);
+        routing.set(Module::Socket, Module::Modem, true);
+        routing.set(Module::Modem, Module::Socket, true);
+
+        routing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routing() {
+        let mut routing = Routing::default();
+        assert!(routing.is_connected(Module::Keyboard, Module::Screen));
+        assert!(!routing.is_connected(Module::Screen, Module::Keyboard));
+
+        routing.set(Module::Keyboard, Module::Screen, false);
+        assert!(!routing.is_connected(Module::Keyboard, Module::Screen));
+        assert_eq!(
+            routing.emitters(Module::Screen).collect::<Vec<_>>(),
+            [Module::Modem]
+        );
+        assert_eq!(
+            routing.to_bytes(Module::Screen),
+            [ESC, PRO3, ROUTING_RESPONSE, SCREEN_RECEIVER, STATUS_BYTE | FROM_MODEM]
+        );
+    }
+
+    #[test]
+    fn test_routing_commands_are_acknowledged() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        for byte in [ESC, PRO3, ROUTING_OFF, SCREEN_RECEIVER, KEYBOARD_EMITTER] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert!(!parser
+            .ctx()
+            .routing
+            .is_connected(Module::Keyboard, Module::Screen));
+        assert_eq!(
+            parser.take_response(),
+            [ESC, PRO3, ROUTING_RESPONSE, SCREEN_RECEIVER, STATUS_BYTE | FROM_MODEM]
+        );
+
+        for byte in [ESC, PRO3, ROUTING_ON, SOCKET_RECEIVER, KEYBOARD_EMITTER] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        parser.take_response();
+
+        for byte in [ESC, PRO2, ROUTING_REQUEST, SOCKET_RECEIVER] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(
+            parser.take_response(),
+            [
+                ESC,
+                PRO3,
+                ROUTING_RESPONSE,
+                SOCKET_RECEIVER,
+                STATUS_BYTE | FROM_KEYBOARD | FROM_MODEM
+            ]
+        );
+
+        parser.set_policy(Policy::Strict);
+        for byte in [ESC, PRO3, ROUTING_ON] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_err!(parser.consume(0x41), "Invalid routing receiver 0x41");
+    }
+
+    #[test]
+    fn test_observed_routing_updates_context() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        parser.observe(&[
+            ESC,
+            PRO3,
+            ROUTING_RESPONSE,
+            SOCKET_RECEIVER,
+            STATUS_BYTE | FROM_KEYBOARD,
+        ]);
+        assert_eq!(
+            parser
+                .ctx()
+                .routing
+                .emitters(Module::Socket)
+                .collect::<Vec<_>>(),
+            [Module::Keyboard]
+        );
+        assert!(!parser.has_response());
+    }
+}
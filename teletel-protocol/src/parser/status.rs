@@ -0,0 +1,115 @@
+use super::*;
+
+/// Functioning status of the terminal, sent as `ESC PRO2 RESP_STATUS <status>`
+/// in response to a status request or to a PRO2 start/stop sequence (p143).
+/// The terminal status register (`ESC PRO1 0x70`) is not decoded.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Status {
+    /// Letters typed on the keyboard are sent as lowercase
+    pub lowercase: bool,
+    /// Error correction procedure (PCE) is enabled
+    pub pce: bool,
+    pub page_mode: PageMode,
+    /// The screen is 80 columns wide, in mixte mode or in the
+    /// téléinformatique standard
+    pub eighty_columns: bool,
+}
+
+impl Status {
+    pub fn from_byte(status: u8) -> Self {
+        let status = status & !STATUS_BYTE;
+
+        Status {
+            lowercase: status & LOWERCASE != 0,
+            pce: status & PCE != 0,
+            page_mode: if status & PAGE_MODE != 0 {
+                PageMode::Scroll
+            } else {
+                PageMode::Page
+            },
+            eighty_columns: status & EIGHTY_COLUMNS != 0,
+        }
+    }
+
+    pub fn to_byte(&self) -> u8 {
+        let mut status = STATUS_BYTE;
+
+        if self.lowercase {
+            status |= LOWERCASE;
+        }
+
+        if self.pce {
+            status |= PCE;
+        }
+
+        if self.page_mode == PageMode::Scroll {
+            status |= PAGE_MODE;
+        }
+
+        if self.eighty_columns {
+            status |= EIGHTY_COLUMNS;
+        }
+
+        status
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_bits() {
+        let status = Status::from_byte(LOWERCASE | PAGE_MODE);
+
+        assert!(status.lowercase);
+        assert!(!status.pce);
+        assert_eq!(status.page_mode, PageMode::Scroll);
+        assert!(!status.eighty_columns);
+        assert_eq!(status.to_byte(), STATUS_BYTE | LOWERCASE | PAGE_MODE);
+
+        let status = Status::from_byte(PCE | EIGHTY_COLUMNS);
+        assert!(status.pce);
+        assert_eq!(status.page_mode, PageMode::Page);
+        assert!(status.eighty_columns);
+    }
+
+    #[test]
+    fn test_observed_status_updates_context() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+
+        parser.observe(&[
+            ESC,
+            PRO2,
+            RESP_STATUS,
+            STATUS_BYTE | LOWERCASE | PAGE_MODE | EIGHTY_COLUMNS,
+        ]);
+        assert!(parser.ctx().lowercase);
+        assert_eq!(parser.ctx().page_mode, PageMode::Scroll);
+        assert_eq!(parser.ctx().mode, Mode::Mixte);
+        assert_eq!(parser.ctx().screen_width, 80);
+        assert_eq!(parser.ctx().grid.cells().len(), 80 * 24);
+        assert!(!parser.has_response());
+
+        //answers with the observed state
+        for byte in [ESC, PRO1, REQ_STATUS] {
+            assert_eq!(parser.consume(byte), Ok(()));
+        }
+
+        assert_eq!(
+            parser.take_response(),
+            vec![
+                ESC,
+                PRO2,
+                RESP_STATUS,
+                STATUS_BYTE | LOWERCASE | PAGE_MODE | EIGHTY_COLUMNS
+            ]
+        );
+
+        //other responses are ignored
+        parser.observe(&[SEP, MIXED_ACK]);
+        parser.observe(&[ESC, PRO2, RESP_STATUS, STATUS_BYTE]);
+        assert_eq!(parser.ctx().status(), STATUS_BYTE);
+        assert_eq!(parser.ctx().screen_width, 40);
+    }
+}
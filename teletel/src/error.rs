@@ -1,38 +1,68 @@
+use alloc::vec::Vec;
+use core::fmt::{Debug, Display};
+#[cfg(feature = "std")]
 use std::error::{Error as StdError};
-use std::fmt::{Debug, Display};
+#[cfg(feature = "std")]
 use std::io::Error as IoError;
-use teletel_protocol::parser::Error as ParseError;
+use teletel_protocol::parser::{Error as ParseError, Feature};
+use crate::terminal::{BaudRate, Response};
 
 pub enum Error {
     ConnectionFailure,
+    Disconnected,
     InvalidCharacter(char),
     UnexpectedSequence(Vec<u8>),
+    UnexpectedResponse(Response),
+    #[cfg(feature = "std")]
     Io(IoError),
     ReadExactEof,
-    Parse(ParseError)
+    Parse(ParseError),
+    Unsupported(Feature),
+    UnsupportedSpeed(BaudRate),
+    #[cfg(feature = "uart-terminal")]
+    Uart(embedded_io::ErrorKind),
 }
 
 impl Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Error::ConnectionFailure => write!(f, "Failed to connect to Minitel"),
+            Error::Disconnected => write!(f, "The Minitel disconnected"),
             Error::InvalidCharacter(ch) => write!(f, "Character {} is not supported by the Minitel", ch),
             Error::UnexpectedSequence(seq) => write!(f, "Unexpected sequence {:X?}", seq),
+            Error::UnexpectedResponse(response) => write!(f, "Unexpected response {:X?}", response),
+            #[cfg(feature = "std")]
             Error::Io(error) => write!(f, "IoError: {}", error),
             Error::ReadExactEof => write!(f, "ReadExactEof"),
-            Error::Parse(error) => write!(f, "ParseError: {}", error)
+            Error::Parse(error) => match (error.byte(), error.offset()) {
+                (Some(byte), Some(offset)) => write!(
+                    f,
+                    "ParseError: {} (byte {:#04X} at offset {}, sequence {:02X?})",
+                    error,
+                    byte,
+                    offset,
+                    error.sequence()
+                ),
+                _ => write!(f, "ParseError: {}", error),
+            },
+            Error::Unsupported(feature) => write!(f, "{:?} is not supported by the terminal", feature),
+            Error::UnsupportedSpeed(speed) => write!(f, "{} bauds are not supported by the terminal", *speed as u16),
+            #[cfg(feature = "uart-terminal")]
+            Error::Uart(kind) => write!(f, "UartError: {:?}", kind),
         }
     }
 }
 
 impl Debug for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self)
     }
 }
 
+#[cfg(feature = "std")]
 impl StdError for Error {}
 
+#[cfg(feature = "std")]
 impl From<IoError> for Error {
     fn from(error: IoError) -> Self {
         Error::Io(error)
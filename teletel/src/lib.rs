@@ -1,3 +1,7 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
 #[macro_use]
 extern crate teletel_derive;
 
@@ -7,6 +11,8 @@ mod macros;
 mod error;
 mod wrapper;
 
+#[cfg(feature = "tcp-terminal")]
+pub mod server;
 pub mod terminal;
 
 pub use error::Error;
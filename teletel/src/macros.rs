@@ -11,13 +11,34 @@ macro_rules! send {
     };
 }
 
+/// Async counterpart of [`send!`] for an [`AsyncWriteableTerminal`], the
+/// codes are written to a buffer that is then sent at once.
+///
+/// [`AsyncWriteableTerminal`]: crate::terminal::AsyncWriteableTerminal
+#[cfg(feature = "async")]
+#[macro_export]
+macro_rules! send_async {
+    ($term:expr, [$($code:expr),+ $(,)?]) => {{
+        let mut buffer = $crate::terminal::RawBuffer::new();
+        $($crate::terminal::ToTerminal::to_terminal(&$code, &mut buffer)?;)+
+
+        match $crate::terminal::AsyncWriteableTerminal::write($term, buffer.data()).await {
+            Ok(()) => $crate::terminal::AsyncWriteableTerminal::flush($term).await,
+            Err(err) => Err(err),
+        }
+    }};
+    ($term:expr, $code:expr) => {
+        send_async!($term, [$code])
+    };
+}
+
 #[macro_export]
 macro_rules! list {
     ($($code:expr),+ $(,)?) => {{
-        |term: &mut dyn $crate::terminal::WriteableTerminal| -> std::result::Result<(), $crate::Error> {
+        |term: &mut dyn $crate::terminal::WriteableTerminal| -> core::result::Result<(), $crate::Error> {
             $($crate::terminal::ToTerminal::to_terminal(&$code, term)?;)+
 
-            std::result::Result::Ok(())
+            core::result::Result::Ok(())
         }
     }};
 }
@@ -0,0 +1,204 @@
+use crate::terminal::{TcpTerminal, TerminalProfile};
+use crate::Error;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::thread;
+use std::time::Duration;
+
+/// Service run for each terminal connected to a [`TcpServer`], on its own
+/// thread. Closures taking the terminal are sessions too.
+pub trait Session: Send + 'static {
+    fn run(&mut self, term: &mut TcpTerminal) -> Result<(), Error>;
+
+    /// Called when the session failed for another reason than the terminal
+    /// disconnecting, errors are written to the standard error output by
+    /// default
+    fn error(&mut self, peer: SocketAddr, error: Error) {
+        eprintln!("Session of {} failed: {}", peer, error);
+    }
+}
+
+impl<F: FnMut(&mut TcpTerminal) -> Result<(), Error> + Send + 'static> Session for F {
+    fn run(&mut self, term: &mut TcpTerminal) -> Result<(), Error> {
+        self(term)
+    }
+}
+
+/// Hosts a service that terminals connect to, through a gateway or from
+/// the emulator. Each connection gets its own terminal, with its own parser
+/// and context, and a session running on its own thread so that a failing
+/// session never disrupts the others.
+///
+/// ```ignore
+/// let server = TcpServer::bind("0.0.0.0:3615")?;
+/// server.serve(|_peer| |term: &mut TcpTerminal| {
+///     send!(term, [Clear, "Bienvenue sur 3615 TELETEL"])?;
+///     term.read_until_enter()?;
+///
+///     Ok(())
+/// })?;
+/// ```
+pub struct TcpServer {
+    listener: TcpListener,
+    profile: TerminalProfile,
+    read_timeout: Option<Duration>,
+}
+
+impl TcpServer {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            profile: TerminalProfile::default(),
+            read_timeout: Some(Duration::from_millis(100)),
+        })
+    }
+
+    /// Describes the terminals expected to connect, a Minitel 2 by default
+    pub fn with_profile(mut self, profile: TerminalProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Changes how long the reads of the sessions wait for data before
+    /// returning 0, 100ms by default. Without timeout, reads block until
+    /// data arrives and [`crate::terminal::ReadableTerminal::read_key`]
+    /// can not time out.
+    pub fn with_read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        self.listener.local_addr().map_err(Into::into)
+    }
+
+    /// Waits for the next terminal to connect
+    pub fn accept(&self) -> Result<(TcpTerminal, SocketAddr), Error> {
+        let (stream, peer) = self.listener.accept()?;
+        stream.set_nodelay(true)?;
+
+        let mut term = TcpTerminal::from_stream(stream, self.profile);
+        term.set_read_timeout(self.read_timeout)?;
+
+        Ok((term, peer))
+    }
+
+    /// Accepts connections forever, running the session created for each
+    /// one on its own thread. Only fails if the server can not accept
+    /// connections anymore.
+    pub fn serve<S: Session, F: FnMut(SocketAddr) -> S>(
+        &self,
+        mut factory: F,
+    ) -> Result<(), Error> {
+        loop {
+            let (term, peer) = match self.accept() {
+                Ok(connection) => connection,
+                //the connection was closed before being accepted
+                Err(Error::Io(err)) if is_disconnect(err.kind()) => continue,
+                Err(err) => return Err(err),
+            };
+
+            let session = factory(peer);
+            thread::Builder::new()
+                .name(format!("session-{}", peer))
+                .spawn(move || run(session, term, peer))?;
+        }
+    }
+}
+
+fn run<S: Session>(mut session: S, mut term: TcpTerminal, peer: SocketAddr) {
+    match session.run(&mut term) {
+        Ok(()) | Err(Error::Disconnected) | Err(Error::ReadExactEof) => {}
+        Err(Error::Io(err)) if is_disconnect(err.kind()) => {}
+        Err(err) => session.error(peer, err),
+    }
+}
+
+fn is_disconnect(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::BrokenPipe
+            | ErrorKind::UnexpectedEof
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::{ReadableTerminal, WriteableTerminal};
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::mpsc::{self, Sender};
+
+    /// Greets the terminal and reports what it answered
+    struct Greeter {
+        answers: Sender<Result<Vec<u8>, String>>,
+    }
+
+    impl Session for Greeter {
+        fn run(&mut self, term: &mut TcpTerminal) -> Result<(), Error> {
+            term.write(b"Bonjour")?;
+            term.flush()?;
+
+            let answer = term.read_until_enter()?;
+            if answer == b"panne" {
+                return Err(Error::InvalidCharacter('!'));
+            }
+
+            self.answers.send(Ok(answer)).unwrap();
+            Ok(())
+        }
+
+        fn error(&mut self, _peer: SocketAddr, error: Error) {
+            self.answers.send(Err(error.to_string())).unwrap();
+        }
+    }
+
+    fn connect(addr: SocketAddr, answer: &[u8]) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).unwrap();
+
+        let mut greeting = [0; 7];
+        stream.read_exact(&mut greeting).unwrap();
+        assert_eq!(&greeting, b"Bonjour");
+
+        stream.write_all(answer).unwrap();
+        stream
+    }
+
+    #[test]
+    fn test_sessions_are_isolated() {
+        let server = TcpServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            server
+                .serve(|_peer| Greeter {
+                    answers: tx.clone(),
+                })
+                .unwrap();
+        });
+
+        let failing = connect(addr, b"panne\r");
+        let first = connect(addr, b"3615");
+        let second = connect(addr, b"ulla\r");
+
+        let timeout = Duration::from_secs(5);
+        let outcomes = [
+            rx.recv_timeout(timeout).unwrap(),
+            rx.recv_timeout(timeout).unwrap(),
+        ];
+        assert!(outcomes.contains(&Ok(b"ulla".to_vec())));
+        assert!(outcomes.contains(&Err(
+            "Character ! is not supported by the Minitel".to_string()
+        )));
+
+        //the first terminal disconnects before pressing enter
+        drop(first);
+        assert!(rx.recv_timeout(Duration::from_millis(500)).is_err());
+
+        drop((failing, second));
+    }
+}
@@ -0,0 +1,227 @@
+#[cfg(feature = "async-serial-terminal")]
+mod serial;
+#[cfg(feature = "tcp-terminal")]
+mod tcp;
+
+use crate::terminal::{InputDecoder, KeyEvent, Response, TerminalProfile};
+use crate::Error;
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(feature = "async-serial-terminal")]
+pub use serial::AsyncSerialTerminal;
+#[cfg(feature = "tcp-terminal")]
+pub use tcp::AsyncTcpTerminal;
+
+/// Non-blocking counterpart of [`super::ReadableTerminal`] driven by tokio,
+/// so that a single thread can serve many terminals.
+///
+/// Unlike blocking terminals, reads wait for data and only return 0 once
+/// the connection is closed.
+pub trait AsyncReadableTerminal: Send {
+    fn read(&mut self, buf: &mut [u8]) -> impl Future<Output = Result<usize, Error>> + Send;
+
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> impl Future<Output = Result<(), Error>> + Send {
+        async move {
+            while !buf.is_empty() {
+                match self.read(buf).await? {
+                    0 => return Err(Error::ReadExactEof),
+                    n => buf = &mut buf[n..],
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Reads until the terminal sends a protocol response, keys typed in
+    /// the meantime are lost
+    fn read_response(&mut self) -> impl Future<Output = Result<Response, Error>> + Send {
+        async move {
+            let mut decoder = InputDecoder::new();
+            let mut byte = [0];
+
+            loop {
+                self.read_exact(&mut byte).await?;
+
+                //invalid sequences are skipped, the decoder starts over
+                if let Ok(Some(KeyEvent::Protocol(response))) = decoder.decode(byte[0]) {
+                    return Ok(response);
+                }
+            }
+        }
+    }
+
+    /// Waits for the next key pressed on the keyboard, protocol responses
+    /// are returned as key events too. A sequence started before the
+    /// timeout is read until its end.
+    fn read_key(
+        &mut self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<Option<KeyEvent>, Error>> + Send {
+        async move {
+            let mut decoder = InputDecoder::new();
+            let mut byte = [0];
+            let deadline = tokio::time::Instant::now() + timeout;
+
+            loop {
+                if decoder.is_pending() {
+                    self.read_exact(&mut byte).await?;
+                } else if let Ok(result) =
+                    tokio::time::timeout_at(deadline, self.read_exact(&mut byte)).await
+                {
+                    result?;
+                } else {
+                    return Ok(None);
+                }
+
+                if let Some(event) = decoder.decode(byte[0])? {
+                    return Ok(Some(event));
+                }
+            }
+        }
+    }
+}
+
+/// Non-blocking counterpart of [`super::WriteableTerminal`], data is sent
+/// with the [`crate::send_async`] macro.
+pub trait AsyncWriteableTerminal: Send {
+    fn write(&mut self, buf: &[u8]) -> impl Future<Output = Result<(), Error>> + Send;
+    fn flush(&mut self) -> impl Future<Output = Result<(), Error>> + Send;
+
+    /// Profile of the terminal if it is known
+    fn profile(&self) -> Option<TerminalProfile> {
+        None
+    }
+
+    /// Changes the profile the terminal validates the sent sequences
+    /// against, ignored by terminals without a parser
+    fn set_profile(&mut self, _profile: TerminalProfile) {}
+
+    /// Updates the context from a response read from the terminal,
+    /// ignored by terminals without a parser
+    fn observe(&mut self, _response: &[u8]) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::{FunctionKey, Optional, Tap, Tee};
+    use std::collections::VecDeque;
+    use teletel_protocol::codes::keyboard::ENVOI;
+    use teletel_protocol::codes::*;
+
+    /// Terminal keeping what is written and returning the given input
+    #[derive(Default)]
+    struct Memory {
+        data: Vec<u8>,
+        input: VecDeque<u8>,
+    }
+
+    impl AsyncReadableTerminal for Memory {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            let count = buf.len().min(self.input.len());
+            for (dst, src) in buf.iter_mut().zip(self.input.drain(..count)) {
+                *dst = src;
+            }
+
+            Ok(count)
+        }
+    }
+
+    impl AsyncWriteableTerminal for Memory {
+        async fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+            self.data.extend(buf);
+            Ok(())
+        }
+
+        async fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    async fn hello<T: AsyncWriteableTerminal>(term: &mut T) -> Result<(), Error> {
+        send_async!(term, [FF, "Bonjour"])
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let mut term = Memory::default();
+        hello(&mut term).await.unwrap();
+
+        assert_eq!(term.data, b"\x0CBonjour");
+    }
+
+    #[tokio::test]
+    async fn test_read_key() {
+        let mut term = Memory::default();
+        term.input.extend([SS2, ACUTE, b'e']);
+        term.input.extend(ENVOI);
+
+        let timeout = Duration::from_millis(10);
+        assert_eq!(
+            term.read_key(timeout).await.unwrap(),
+            Some(KeyEvent::Char('é'))
+        );
+        assert_eq!(
+            term.read_key(timeout).await.unwrap(),
+            Some(KeyEvent::Function(FunctionKey::Envoi))
+        );
+        assert!(matches!(
+            term.read_key(timeout).await,
+            Err(Error::ReadExactEof)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_key_waits_for_sequence_end() {
+        /// Terminal whose bytes arrive slower than the timeout once the
+        /// first one was read
+        struct SlowLine {
+            input: VecDeque<u8>,
+            started: bool,
+        }
+
+        impl AsyncReadableTerminal for SlowLine {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+                if self.started {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+
+                self.started = true;
+                buf[0] = self.input.pop_front().unwrap();
+
+                Ok(1)
+            }
+        }
+
+        let mut term = SlowLine {
+            input: VecDeque::from([SS2, ACUTE, b'e']),
+            started: false,
+        };
+
+        assert_eq!(
+            term.read_key(Duration::from_millis(10)).await.unwrap(),
+            Some(KeyEvent::Char('é'))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_combinators() {
+        let mut tee = Tee::new(
+            Memory::default(),
+            Tap::new(Memory::default(), Memory::default()),
+        );
+        hello(&mut tee).await.unwrap();
+
+        assert_eq!(tee.primary.data, b"\x0CBonjour");
+        assert_eq!(tee.secondary.inner.data, b"\x0CBonjour");
+        assert_eq!(tee.secondary.logger.data, b"\x0CBonjour");
+
+        let mut optional = Optional::new(Err::<Memory, _>(Error::ConnectionFailure));
+        hello(&mut optional).await.unwrap();
+
+        let mut buf = [0; 4];
+        assert_eq!(optional.read(&mut buf).await.unwrap(), 0);
+    }
+}
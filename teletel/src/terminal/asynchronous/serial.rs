@@ -0,0 +1,121 @@
+use crate::terminal::{
+    AsyncReadableTerminal, AsyncWriteableTerminal, BaudRate, Context, Contextualized, LocalEcho,
+    RawBuffer, ToTerminal,
+};
+use crate::Error;
+use std::time::Duration;
+use teletel_protocol::parser::{Parser, TerminalProfile};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_serial::{
+    DataBits, FlowControl, Parity, SerialPort, SerialPortBuilderExt, SerialStream, StopBits,
+};
+
+/// A terminal connected to a serial port without blocking the thread.
+///
+/// Unlike [`crate::terminal::SerialTerminal`], the connector speed is not
+/// detected and must match the speed the terminal is set to.
+pub struct AsyncSerialTerminal {
+    port: SerialStream,
+    baud_rate: BaudRate,
+    parser: Parser,
+    echo: LocalEcho,
+}
+
+impl AsyncSerialTerminal {
+    pub fn new<S: AsRef<str>>(path: S, baud_rate: BaudRate) -> Result<Self, Error> {
+        Self::with_profile(path, baud_rate, TerminalProfile::default())
+    }
+
+    /// Opens the serial port of a terminal described by the given profile
+    pub fn with_profile<S: AsRef<str>>(
+        path: S,
+        baud_rate: BaudRate,
+        profile: TerminalProfile,
+    ) -> Result<Self, Error> {
+        let port = tokio_serial::new(path.as_ref(), baud_rate as u32)
+            .data_bits(DataBits::Seven)
+            .parity(Parity::Even)
+            .stop_bits(StopBits::One)
+            .flow_control(FlowControl::None)
+            .open_native_async()
+            .map_err(std::io::Error::from)?;
+
+        Ok(Self {
+            port,
+            baud_rate,
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+        })
+    }
+
+    pub fn baud_rate(&self) -> BaudRate {
+        self.baud_rate
+    }
+
+    /// Programs the speed of the connector of the terminal and switches the
+    /// serial port to it
+    pub async fn set_connector_speed(&mut self, baud_rate: BaudRate) -> Result<(), Error> {
+        if !self.parser.profile().supports_speed(baud_rate as u16) {
+            return Err(Error::UnsupportedSpeed(baud_rate));
+        }
+
+        let mut buffer = RawBuffer::new();
+        baud_rate.to_terminal(&mut buffer)?;
+        self.write(buffer.data()).await?;
+        self.flush().await?;
+
+        //same delay as the blocking terminal, the terminal ignores what
+        //it receives while switching
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        self.port
+            .set_baud_rate(baud_rate as u32)
+            .map_err(std::io::Error::from)?;
+        self.baud_rate = baud_rate;
+
+        Ok(())
+    }
+}
+
+impl AsyncReadableTerminal for AsyncSerialTerminal {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let bytes_read = self.port.read(buf).await?;
+        self.echo.feed(&mut self.parser, &buf[..bytes_read]);
+
+        Ok(bytes_read)
+    }
+}
+
+impl AsyncWriteableTerminal for AsyncSerialTerminal {
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for (i, byte) in buf.iter().enumerate() {
+            if let Err(err) = self.parser.consume(*byte) {
+                self.port.write_all(&buf[..i]).await?;
+                return Err(err.into());
+            }
+        }
+
+        self.port.write_all(buf).await.map_err(Into::into)
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        self.port.flush().await.map_err(Into::into)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl Contextualized for AsyncSerialTerminal {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
@@ -0,0 +1,95 @@
+use crate::terminal::{
+    AsyncReadableTerminal, AsyncWriteableTerminal, Context, Contextualized, LocalEcho,
+};
+use crate::Error;
+use teletel_protocol::parser::{Parser, TerminalProfile};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+/// A terminal that communicates over TCP without blocking the thread.
+pub struct AsyncTcpTerminal {
+    stream: TcpStream,
+    parser: Parser,
+    echo: LocalEcho,
+}
+
+impl AsyncTcpTerminal {
+    /// Connect to `127.0.0.1:3615`.
+    pub async fn emulator() -> Result<Self, Error> {
+        Self::connect_to(("127.0.0.1", 3615)).await
+    }
+
+    /// Connect to a specific address.
+    pub async fn connect_to<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        Self::connect_with_profile(addr, TerminalProfile::default()).await
+    }
+
+    /// Connect to a specific address, the remote terminal being described
+    /// by the given profile.
+    pub async fn connect_with_profile<A: ToSocketAddrs>(
+        addr: A,
+        profile: TerminalProfile,
+    ) -> Result<Self, Error> {
+        let stream = TcpStream::connect(addr).await.map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("could not connect: {e}"),
+            ))
+        })?;
+
+        Ok(Self::from_stream(stream, profile))
+    }
+
+    /// Wraps an established connection, such as one accepted by a server.
+    pub fn from_stream(stream: TcpStream, profile: TerminalProfile) -> Self {
+        Self {
+            stream,
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+        }
+    }
+}
+
+impl AsyncReadableTerminal for AsyncTcpTerminal {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let bytes_read = self.stream.read(buf).await?;
+        self.echo.feed(&mut self.parser, &buf[..bytes_read]);
+
+        Ok(bytes_read)
+    }
+}
+
+impl AsyncWriteableTerminal for AsyncTcpTerminal {
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for (i, byte) in buf.iter().enumerate() {
+            if let Err(err) = self.parser.consume(*byte) {
+                self.stream.write_all(&buf[..i]).await?;
+                return Err(err.into());
+            }
+        }
+
+        self.stream.write_all(buf).await.map_err(Into::into)
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        self.stream.flush().await.map_err(Into::into)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl Contextualized for AsyncTcpTerminal {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
@@ -1,5 +1,5 @@
-use std::io::Write;
-use teletel_protocol::parser::{DisplayComponent, Parser};
+use alloc::vec::Vec;
+use teletel_protocol::parser::{Parser, Standard, TerminalProfile};
 use crate::Error;
 use crate::terminal::{Context, Contextualized, ToTerminal, WriteableTerminal};
 
@@ -13,6 +13,13 @@ impl Buffer {
         Buffer::default()
     }
 
+    pub fn with_profile(profile: TerminalProfile) -> Buffer {
+        Buffer {
+            inner: Vec::new(),
+            parser: Parser::from_profile(profile),
+        }
+    }
+
     pub fn data(&self) -> &[u8] {
         &self.inner
     }
@@ -25,10 +32,7 @@ impl Buffer {
 
 impl Default for Buffer {
     fn default() -> Self {
-        Buffer {
-            inner: Vec::new(),
-            parser: Parser::new(DisplayComponent::VGP5),
-        }
+        Buffer::with_profile(TerminalProfile::default())
     }
 }
 
@@ -51,7 +55,23 @@ impl WriteableTerminal for Buffer {
 
     #[inline(always)]
     fn flush(&mut self) -> Result<(), Error> {
-        self.inner.flush().map_err(Into::into)
+        Ok(())
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
     }
 }
 
@@ -86,7 +106,7 @@ impl WriteableTerminal for RawBuffer {
 
     #[inline(always)]
     fn flush(&mut self) -> Result<(), Error> {
-        self.inner.flush().map_err(Into::into)
+        Ok(())
     }
 }
 
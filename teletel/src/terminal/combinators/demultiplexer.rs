@@ -0,0 +1,220 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::io::ErrorKind;
+use crate::terminal::{
+    Context, Contextualized, InputDecoder, KeyEvent, ReadableTerminal, Response, TerminalProfile,
+    WriteableTerminal,
+};
+use crate::Error;
+use teletel_protocol::parser::Standard;
+
+/// Separates the protocol responses of a terminal from the keys typed on
+/// its keyboard.
+///
+/// Responses are kept for the protocol requests waiting for them while
+/// keys are queued and returned by [`ReadableTerminal::read`], so that a
+/// protocol request never eats the input of the user.
+pub struct Demultiplexer<T> {
+    pub inner: T,
+    decoder: InputDecoder,
+    /// Bytes of the sequence being decoded
+    sequence: Vec<u8>,
+    input: VecDeque<u8>,
+    responses: VecDeque<Response>,
+}
+
+impl<T> Demultiplexer<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            decoder: InputDecoder::new(),
+            sequence: Vec::new(),
+            input: VecDeque::new(),
+            responses: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        self.sequence.push(byte);
+
+        match self.decoder.decode(byte) {
+            Ok(None) => {}
+            Ok(Some(KeyEvent::Protocol(response))) => {
+                self.responses.push_back(response);
+                self.sequence.clear();
+            }
+            //invalid sequences are left to the application
+            Ok(Some(_)) | Err(_) => self.input.extend(self.sequence.drain(..)),
+        }
+    }
+}
+
+impl<T: ReadableTerminal> Demultiplexer<T> {
+    /// Reads the available bytes from the inner terminal
+    fn fill(&mut self) -> Result<usize, Error> {
+        let mut buffer = [0; 64];
+        let bytes_read = self.inner.read(&mut buffer)?;
+
+        for byte in &buffer[..bytes_read] {
+            self.push(*byte);
+        }
+
+        Ok(bytes_read)
+    }
+}
+
+impl<T: ReadableTerminal> ReadableTerminal for Demultiplexer<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.input.is_empty() {
+            self.fill()?;
+        }
+
+        let count = buf.len().min(self.input.len());
+        for (dst, src) in buf.iter_mut().zip(self.input.drain(..count)) {
+            *dst = src;
+        }
+
+        Ok(count)
+    }
+
+    /// Drops the responses that are not waited for, keys are kept
+    fn discard(&mut self) -> Result<(), Error> {
+        loop {
+            match self.fill() {
+                Ok(0) => break,
+                Ok(_) => {}
+                #[cfg(feature = "std")]
+                Err(Error::Io(ref e))
+                    if matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) =>
+                {
+                    break
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.responses.clear();
+
+        Ok(())
+    }
+
+    fn read_response(&mut self) -> Result<Response, Error> {
+        loop {
+            if let Some(response) = self.responses.pop_front() {
+                return Ok(response);
+            }
+
+            if self.fill()? == 0 {
+                return Err(Error::ReadExactEof);
+            }
+        }
+    }
+
+    fn wait(&mut self, duration: Duration) -> Duration {
+        self.inner.wait(duration)
+    }
+}
+
+impl<T: WriteableTerminal> WriteableTerminal for Demultiplexer<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.inner.flush()
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.inner.profile()
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        self.inner.standard()
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.inner.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.inner.observe(response);
+    }
+}
+
+impl<T: Contextualized> Contextualized for Demultiplexer<T> {
+    fn ctx(&self) -> &Context {
+        self.inner.ctx()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::FunctionKey;
+    use std::time::Duration;
+    use teletel_protocol::codes::keyboard::ENVOI;
+    use teletel_protocol::codes::*;
+
+    /// Terminal returning the given bytes two at a time
+    struct Keyboard(VecDeque<u8>);
+
+    impl ReadableTerminal for Keyboard {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            let count = buf.len().min(self.0.len()).min(2);
+            for (dst, src) in buf.iter_mut().zip(self.0.drain(..count)) {
+                *dst = src;
+            }
+
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn keys_are_kept_while_waiting_for_responses() {
+        let mut bytes = vec![b'a', ESC, PRO2, RESP_SPEED, B1200];
+        bytes.extend(ENVOI);
+        bytes.extend([SEP, 0x5E, b'b']);
+
+        let mut demux = Demultiplexer::new(Keyboard(bytes.into()));
+
+        assert!(matches!(demux.read_response(), Ok(Response::Speed(_))));
+        assert_eq!(demux.read_response().unwrap(), Response::Acknowledgement(0x5E));
+
+        let timeout = Duration::from_millis(10);
+        assert_eq!(demux.read_key(timeout).unwrap(), Some(KeyEvent::Char('a')));
+        assert_eq!(
+            demux.read_key(timeout).unwrap(),
+            Some(KeyEvent::Function(FunctionKey::Envoi))
+        );
+        assert_eq!(demux.read_key(timeout).unwrap(), Some(KeyEvent::Char('b')));
+        assert!(matches!(demux.read_response(), Err(Error::ReadExactEof)));
+    }
+
+    #[test]
+    fn discard_keeps_keys() {
+        let mut demux = Demultiplexer::new(Keyboard(vec![SEP, 0x72, b'x'].into()));
+
+        demux.discard().unwrap();
+        assert!(matches!(demux.read_response(), Err(Error::ReadExactEof)));
+
+        let mut buf = [0; 4];
+        assert_eq!(demux.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], b'x');
+    }
+
+    #[test]
+    fn discard_returns_errors() {
+        struct Unplugged;
+
+        impl ReadableTerminal for Unplugged {
+            fn read(&mut self, _buf: &mut [u8]) -> Result<usize, Error> {
+                Err(Error::Disconnected)
+            }
+        }
+
+        let mut demux = Demultiplexer::new(Unplugged);
+        assert!(matches!(demux.discard(), Err(Error::Disconnected)));
+    }
+}
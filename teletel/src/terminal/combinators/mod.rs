@@ -1,9 +1,13 @@
+mod demultiplexer;
 mod optional;
+mod pce;
 mod pipe;
 mod tap;
 mod tee;
 
+pub use demultiplexer::Demultiplexer;
 pub use optional::Optional;
+pub use pce::Pce;
 pub use pipe::{pipe, bidirectional_pipe};
 pub use tap::Tap;
 pub use tee::Tee;
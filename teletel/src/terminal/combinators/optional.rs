@@ -1,5 +1,10 @@
-use crate::terminal::{ReadableTerminal, WriteableTerminal};
+use crate::terminal::{KeyEvent, ReadableTerminal, TerminalProfile, WriteableTerminal};
+#[cfg(feature = "async")]
+use crate::terminal::{AsyncReadableTerminal, AsyncWriteableTerminal};
 use crate::Error;
+use alloc::collections::VecDeque;
+use core::time::Duration;
+use teletel_protocol::parser::Standard;
 
 /// A terminal that may or may not be connected.
 ///
@@ -47,6 +52,26 @@ impl<T: WriteableTerminal> WriteableTerminal for Optional<T> {
         }
         Ok(())
     }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.inner.as_ref().and_then(|inner| inner.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        self.inner.as_ref().and_then(|inner| inner.standard())
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        if let Some(inner) = &mut self.inner {
+            inner.set_profile(profile);
+        }
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        if let Some(inner) = &mut self.inner {
+            inner.observe(response);
+        }
+    }
 }
 
 impl<T: ReadableTerminal> ReadableTerminal for Optional<T> {
@@ -59,6 +84,72 @@ impl<T: ReadableTerminal> ReadableTerminal for Optional<T> {
         }
         Ok(0)
     }
+
+    fn wait(&mut self, duration: Duration) -> Duration {
+        match &mut self.inner {
+            Some(inner) => inner.wait(duration),
+            None => {
+                #[cfg(feature = "std")]
+                std::thread::sleep(duration);
+
+                duration
+            }
+        }
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        self.inner.as_mut().and_then(|inner| inner.pending_keys())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncWriteableTerminal> AsyncWriteableTerminal for Optional<T> {
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        if let Some(inner) = &mut self.inner {
+            if inner.write(buf).await.is_err() {
+                self.inner = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        if let Some(inner) = &mut self.inner {
+            if inner.flush().await.is_err() {
+                self.inner = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.inner.as_ref().and_then(|inner| inner.profile())
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        if let Some(inner) = &mut self.inner {
+            inner.set_profile(profile);
+        }
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        if let Some(inner) = &mut self.inner {
+            inner.observe(response);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncReadableTerminal> AsyncReadableTerminal for Optional<T> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if let Some(inner) = &mut self.inner {
+            match inner.read(buf).await {
+                Ok(n) => return Ok(n),
+                Err(_) => self.inner = None,
+            }
+        }
+        Ok(0)
+    }
 }
 
 #[cfg(test)]
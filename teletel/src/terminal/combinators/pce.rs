@@ -0,0 +1,407 @@
+use crate::protocol::ProtocolExtension;
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, ReadableTerminal, Response, TerminalProfile,
+    WriteableTerminal,
+};
+use crate::Error;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+use teletel_protocol::codes::pce::{BLOCK_NUMBER, NACK, SYN};
+use teletel_protocol::codes::{ESC, PRO2, PROCEDURE, START, STOP};
+use teletel_protocol::parser::{Block, Standard};
+
+/// Number of blocks the terminal can ask to send again
+const HISTORY: usize = 16;
+
+/// Sends data to a terminal through the error correction procedure (PCE)
+/// so that bytes corrupted on noisy lines are sent again.
+///
+/// Once [`Pce::enable`] succeeded, writes are framed in blocks of 15 bytes
+/// and [`WriteableTerminal::flush`] pads the last block. Retransmission
+/// requests are answered while reading, they must thus be read regularly
+/// and never reach the application. A [`super::Demultiplexer`] must wrap
+/// the `Pce` and not the other way around.
+///
+/// Documented on page 146.
+pub struct Pce<T> {
+    pub inner: T,
+    enabled: bool,
+    /// Data of the block being filled
+    block: Vec<u8>,
+    /// Last blocks sent, the most recent at the back
+    sent: VecDeque<[u8; Block::FRAMED_LENGTH]>,
+    /// Number of the next block
+    number: u8,
+    input: VecDeque<u8>,
+    /// Whether a NACK was read without the number of the block
+    nack: bool,
+}
+
+impl<T> Pce<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            enabled: false,
+            block: Vec::with_capacity(Block::LENGTH),
+            sent: VecDeque::with_capacity(HISTORY),
+            number: 0,
+            input: VecDeque::new(),
+            nack: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+impl<T: WriteableTerminal> Pce<T> {
+    /// Frames the pending data, padded with NUL bytes
+    fn send_block(&mut self) -> Result<(), Error> {
+        let block = Block::frame(&self.block);
+        self.block.clear();
+
+        if self.sent.len() == HISTORY {
+            self.sent.pop_front();
+        }
+
+        self.sent.push_back(block);
+        self.number = (self.number + 1) % HISTORY as u8;
+
+        self.inner.write(&block)
+    }
+}
+
+impl<T: ReadableTerminal + WriteableTerminal> Pce<T> {
+    /// Enables the procedure on the terminal, writes are framed once it
+    /// reported the procedure in its functioning status
+    pub fn enable(&mut self) -> Result<(), Error> {
+        if self.enabled {
+            return Ok(());
+        }
+
+        self.inner.discard()?;
+        self.inner.write(&[ESC, PRO2, START, PROCEDURE])?;
+        self.inner.flush()?;
+
+        let status = self.inner.functional_state()?;
+        if !status.pce {
+            return Err(Error::UnexpectedResponse(Response::Status(status)));
+        }
+
+        self.enabled = true;
+        self.block.clear();
+        self.sent.clear();
+        self.number = 0;
+
+        Ok(())
+    }
+
+    /// Disables the procedure, the request itself being sent framed
+    pub fn disable(&mut self) -> Result<(), Error> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        self.write(&[ESC, PRO2, STOP, PROCEDURE])?;
+        self.flush()?;
+
+        let status = self.functional_state()?;
+        if status.pce {
+            return Err(Error::UnexpectedResponse(Response::Status(status)));
+        }
+
+        self.enabled = false;
+
+        Ok(())
+    }
+
+    /// Sends the blocks again starting from the corrupted one
+    fn retransmit(&mut self, code: u8) -> Result<(), Error> {
+        let number = code.wrapping_sub(BLOCK_NUMBER) as usize;
+        if number >= HISTORY {
+            return Err(Error::UnexpectedSequence(vec![NACK, code]));
+        }
+
+        //how many blocks were sent after the corrupted one
+        let age = (self.number as usize + HISTORY - 1 - number) % HISTORY;
+        if age >= self.sent.len() {
+            return Err(Error::UnexpectedSequence(vec![NACK, code]));
+        }
+
+        self.inner.write(&[SYN, SYN, code])?;
+        for block in self.sent.range(self.sent.len() - 1 - age..) {
+            self.inner.write(block)?;
+        }
+
+        self.inner.flush()
+    }
+}
+
+impl<T: ReadableTerminal + WriteableTerminal> ReadableTerminal for Pce<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let mut buffer = [0; 64];
+
+        //retransmission requests alone do not count as data read
+        while self.input.is_empty() {
+            let bytes_read = self.inner.read(&mut buffer)?;
+            if bytes_read == 0 {
+                break;
+            }
+
+            for byte in &buffer[..bytes_read] {
+                if self.nack {
+                    self.nack = false;
+                    self.retransmit(*byte)?;
+                } else if *byte == NACK && self.enabled {
+                    self.nack = true;
+                } else {
+                    self.input.push_back(*byte);
+                }
+            }
+        }
+
+        let count = buf.len().min(self.input.len());
+        for (dst, src) in buf.iter_mut().zip(self.input.drain(..count)) {
+            *dst = src;
+        }
+
+        Ok(count)
+    }
+
+    fn wait(&mut self, duration: Duration) -> Duration {
+        self.inner.wait(duration)
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        self.inner.pending_keys()
+    }
+}
+
+impl<T: WriteableTerminal> WriteableTerminal for Pce<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        if !self.enabled {
+            return self.inner.write(buf);
+        }
+
+        for byte in buf {
+            self.block.push(*byte);
+
+            if self.block.len() == Block::LENGTH {
+                self.send_block()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends the pending data in a block padded with NUL bytes
+    fn flush(&mut self) -> Result<(), Error> {
+        if self.enabled && !self.block.is_empty() {
+            self.send_block()?;
+        }
+
+        self.inner.flush()
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.inner.profile()
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        self.inner.standard()
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.inner.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.inner.observe(response);
+    }
+}
+
+impl<T: Contextualized> Contextualized for Pce<T> {
+    fn ctx(&self) -> &Context {
+        self.inner.ctx()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teletel_protocol::parser::{DisplayComponent, Parser};
+
+    /// Terminal parsing what it receives after flipping a bit of some of
+    /// the bytes, as a noisy line would
+    struct NoisyLine {
+        parser: Parser,
+        /// Offsets of the bytes to corrupt
+        noise: Vec<usize>,
+        offset: usize,
+        response: VecDeque<u8>,
+    }
+
+    impl NoisyLine {
+        fn new(noise: Vec<usize>) -> Self {
+            Self {
+                parser: Parser::new(DisplayComponent::VGP5),
+                noise,
+                offset: 0,
+                response: VecDeque::new(),
+            }
+        }
+    }
+
+    impl ReadableTerminal for NoisyLine {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            let count = buf.len().min(self.response.len());
+            for (dst, src) in buf.iter_mut().zip(self.response.drain(..count)) {
+                *dst = src;
+            }
+
+            Ok(count)
+        }
+    }
+
+    impl WriteableTerminal for NoisyLine {
+        fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+            for byte in buf {
+                let byte = if self.noise.contains(&self.offset) {
+                    byte ^ 0x10
+                } else {
+                    *byte
+                };
+
+                self.offset += 1;
+                self.parser.consume(byte)?;
+            }
+
+            self.response.extend(self.parser.take_response());
+
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl Contextualized for NoisyLine {
+        fn ctx(&self) -> &Context {
+            self.parser.ctx()
+        }
+    }
+
+    /// Terminal keeping the context of what it sends in its own parser, as
+    /// the terminals of the crate do
+    struct Host {
+        parser: Parser,
+        line: NoisyLine,
+    }
+
+    impl ReadableTerminal for Host {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            self.line.read(buf)
+        }
+    }
+
+    impl WriteableTerminal for Host {
+        fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+            for byte in buf {
+                self.parser.consume(*byte)?;
+            }
+
+            self.line.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl Contextualized for Host {
+        fn ctx(&self) -> &Context {
+            self.parser.ctx()
+        }
+    }
+
+    #[test]
+    fn corrupted_blocks_are_sent_again() {
+        //corrupts the second block and the third one once sent again
+        let mut pce = Pce::new(NoisyLine::new(vec![4 + 17 + 3, 4 + 17 * 4 + 3 + 17 + 3]));
+        pce.enable().unwrap();
+        assert!(pce.is_enabled());
+
+        let text = b"Le Minitel est un terminal passif de consultation";
+        pce.write(text).unwrap();
+        pce.flush().unwrap();
+
+        let mut buf = [0; 8];
+        assert_eq!(pce.read(&mut buf).unwrap(), 0);
+        assert_eq!(pce.read(&mut buf).unwrap(), 0);
+        assert_eq!(pce.inner.offset, 4 + 17 * 4 + (3 + 17 * 3) + (3 + 17 * 2));
+
+        let displayed = (0..text.len() as u8)
+            .map(|i| pce.ctx().grid.cell(i % 40 + 1, i / 40 + 1).content)
+            .collect::<String>();
+        assert_eq!(displayed.as_bytes(), text);
+
+        pce.disable().unwrap();
+        assert!(!pce.is_enabled());
+        assert!(!pce.ctx().pce);
+    }
+
+    #[test]
+    fn host_context_follows_retransmissions() {
+        //corrupts the second block
+        let mut pce = Pce::new(Host {
+            parser: Parser::new(DisplayComponent::VGP5),
+            line: NoisyLine::new(vec![4 + 17 + 3]),
+        });
+        pce.enable().unwrap();
+
+        let mut buf = [0; 8];
+        for text in [&b"abc"[..], b"Minitel", b"def"] {
+            pce.write(text).unwrap();
+            pce.flush().unwrap();
+            assert_eq!(pce.read(&mut buf).unwrap(), 0);
+        }
+
+        let host = &pce.inner;
+        for x in 1..=40 {
+            assert_eq!(
+                host.ctx().grid.cell(x, 1).content,
+                host.line.ctx().grid.cell(x, 1).content
+            );
+        }
+        assert_eq!(host.ctx().grid.cell(11, 1).content, 'd');
+        assert_eq!(host.ctx().cursor_x, 14);
+        assert_eq!(host.line.ctx().cursor_x, 14);
+
+        let response = pce.inner.parser.take_response();
+        assert!(!response.contains(&NACK));
+    }
+
+    #[test]
+    fn keys_are_kept_around_retransmission_requests() {
+        let mut pce = Pce::new(NoisyLine::new(Vec::new()));
+        pce.enable().unwrap();
+        pce.write(b"hello").unwrap();
+        pce.flush().unwrap();
+
+        pce.inner.response.extend([b'a', NACK, BLOCK_NUMBER, b'b']);
+
+        let mut buf = [0; 8];
+        assert_eq!(pce.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], b"ab");
+
+        pce.inner.response.extend([NACK, BLOCK_NUMBER + 5]);
+        assert!(matches!(
+            pce.read(&mut buf),
+            Err(Error::UnexpectedSequence(_))
+        ));
+    }
+}
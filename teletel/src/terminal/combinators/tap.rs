@@ -1,5 +1,13 @@
-use crate::terminal::{Context, Contextualized, ReadableTerminal, WriteableTerminal};
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, ReadableTerminal, Response, TerminalProfile,
+    WriteableTerminal,
+};
+#[cfg(feature = "async")]
+use crate::terminal::{AsyncReadableTerminal, AsyncWriteableTerminal};
 use crate::Error;
+use alloc::collections::VecDeque;
+use core::time::Duration;
+use teletel_protocol::parser::Standard;
 
 /// Writes to a terminal while logging bytes to a side-channel.
 ///
@@ -26,12 +34,76 @@ impl<T: WriteableTerminal, L: WriteableTerminal> WriteableTerminal for Tap<T, L>
         let _ = self.logger.flush();
         self.inner.flush()
     }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.inner.profile()
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        self.inner.standard()
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.inner.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.inner.observe(response);
+    }
 }
 
 impl<T: ReadableTerminal, L> ReadableTerminal for Tap<T, L> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
         self.inner.read(buf)
     }
+
+    fn discard(&mut self) -> Result<(), Error> {
+        self.inner.discard()
+    }
+
+    fn read_response(&mut self) -> Result<Response, Error> {
+        self.inner.read_response()
+    }
+
+    fn wait(&mut self, duration: Duration) -> Duration {
+        self.inner.wait(duration)
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        self.inner.pending_keys()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncWriteableTerminal, L: AsyncWriteableTerminal> AsyncWriteableTerminal for Tap<T, L> {
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        let _ = self.logger.write(buf).await;
+        self.inner.write(buf).await
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        let _ = self.logger.flush().await;
+        self.inner.flush().await
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.inner.profile()
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.inner.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.inner.observe(response);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncReadableTerminal, L: Send> AsyncReadableTerminal for Tap<T, L> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.inner.read(buf).await
+    }
 }
 
 impl<T: Contextualized, L> Contextualized for Tap<T, L> {
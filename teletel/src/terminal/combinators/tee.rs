@@ -1,5 +1,13 @@
-use crate::terminal::{Context, Contextualized, ReadableTerminal, WriteableTerminal};
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, ReadableTerminal, Response, TerminalProfile,
+    WriteableTerminal,
+};
+#[cfg(feature = "async")]
+use crate::terminal::{AsyncReadableTerminal, AsyncWriteableTerminal};
 use crate::Error;
+use alloc::collections::VecDeque;
+use core::time::Duration;
+use teletel_protocol::parser::Standard;
 
 /// Writes to two terminals simultaneously.
 ///
@@ -28,12 +36,82 @@ impl<P: WriteableTerminal, S: WriteableTerminal> WriteableTerminal for Tee<P, S>
         let r2 = self.secondary.flush();
         r1.and(r2)
     }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.primary.profile()
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        self.primary.standard()
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.primary.set_profile(profile);
+        self.secondary.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.primary.observe(response);
+        self.secondary.observe(response);
+    }
 }
 
 impl<P: ReadableTerminal, S> ReadableTerminal for Tee<P, S> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
         self.primary.read(buf)
     }
+
+    fn discard(&mut self) -> Result<(), Error> {
+        self.primary.discard()
+    }
+
+    fn read_response(&mut self) -> Result<Response, Error> {
+        self.primary.read_response()
+    }
+
+    fn wait(&mut self, duration: Duration) -> Duration {
+        self.primary.wait(duration)
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        self.primary.pending_keys()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: AsyncWriteableTerminal, S: AsyncWriteableTerminal> AsyncWriteableTerminal for Tee<P, S> {
+    async fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        let r1 = self.primary.write(buf).await;
+        let r2 = self.secondary.write(buf).await;
+        r1.and(r2)
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        let r1 = self.primary.flush().await;
+        let r2 = self.secondary.flush().await;
+        r1.and(r2)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        self.primary.profile()
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.primary.set_profile(profile);
+        self.secondary.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.primary.observe(response);
+        self.secondary.observe(response);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: AsyncReadableTerminal, S: Send> AsyncReadableTerminal for Tee<P, S> {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.primary.read(buf).await
+    }
 }
 
 impl<P: Contextualized, S> Contextualized for Tee<P, S> {
@@ -3,7 +3,7 @@ use std::io::{Result as IoResult, Write};
 use crate::Error;
 use crate::terminal::{Context, Contextualized, ToTerminal, WriteableTerminal};
 use std::path::Path;
-use teletel_protocol::parser::{DisplayComponent, Parser};
+use teletel_protocol::parser::{Parser, Standard, TerminalProfile};
 
 pub struct FileReceiver {
     file: File,
@@ -12,6 +12,10 @@ pub struct FileReceiver {
 
 impl FileReceiver {
     pub fn new<P: AsRef<Path>>(path: P) -> IoResult<Self> {
+        Self::with_profile(path, TerminalProfile::default())
+    }
+
+    pub fn with_profile<P: AsRef<Path>>(path: P, profile: TerminalProfile) -> IoResult<Self> {
         let file = OpenOptions::new()
             .create(true)
             .truncate(true)
@@ -21,7 +25,7 @@ impl FileReceiver {
 
         Ok(Self {
             file,
-            parser: Parser::new(DisplayComponent::VGP5),
+            parser: Parser::from_profile(profile),
         })
     }
 
@@ -51,6 +55,22 @@ impl WriteableTerminal for FileReceiver {
     fn flush(&mut self) -> Result<(), Error> {
         self.file.flush().map_err(Into::into)
     }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
 }
 
 #[cfg(test)]
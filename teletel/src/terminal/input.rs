@@ -0,0 +1,301 @@
+use crate::terminal::{BaudRate, Identification, Module, Status};
+use crate::Error;
+use alloc::vec::Vec;
+use teletel_protocol::codes::keyboard::*;
+use teletel_protocol::codes::teleinformatique::PRIVATE;
+use teletel_protocol::codes::*;
+use teletel_protocol::parser::{special_character, Direction, DisplayComponent, Parser};
+
+/// Keys of the Minitel keyboard sending a SEP sequence (p38 to 40)
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum FunctionKey {
+    Envoi,
+    Retour,
+    Repetition,
+    Guide,
+    Annulation,
+    Sommaire,
+    Correction,
+    Suite,
+    ConnexionFin,
+}
+
+impl FunctionKey {
+    pub fn from_code(code: [u8; 2]) -> Option<Self> {
+        let key = match code {
+            ENVOI => FunctionKey::Envoi,
+            RETOUR => FunctionKey::Retour,
+            REPETITION => FunctionKey::Repetition,
+            GUIDE => FunctionKey::Guide,
+            ANNULATION => FunctionKey::Annulation,
+            SOMMAIRE => FunctionKey::Sommaire,
+            CORRECTION => FunctionKey::Correction,
+            SUITE => FunctionKey::Suite,
+            CONNEXION_FIN => FunctionKey::ConnexionFin,
+            _ => return None,
+        };
+
+        Some(key)
+    }
+}
+
+/// Answers of the terminal to the protocol sequences it received
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum Response {
+    Status(Status),
+    Speed(BaudRate),
+    /// Receiver and the bits of the emitters connected to it
+    Routing(Module, u8),
+    Identification(Identification),
+    /// Row and column of the cursor
+    CursorPosition(u8, u8),
+    /// Acknowledgement of a reset, mode switch or standby sent as
+    /// `SEP <code>`
+    Acknowledgement(u8),
+    /// Any other escape sequence, kept as is
+    Other(Vec<u8>),
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum KeyEvent {
+    Char(char),
+    Function(FunctionKey),
+    /// Cursor keys, only sent when the keyboard is in extended mode
+    Arrow(Direction),
+    Protocol(Response),
+}
+
+/// Turns the bytes sent by the terminal into key events. Bytes are fed one
+/// at a time and sequences spanning several bytes are kept until complete.
+#[derive(Default)]
+pub struct InputDecoder {
+    buffer: Vec<u8>,
+}
+
+impl InputDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if a sequence has been started but is not complete yet
+    pub fn is_pending(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    /// Decodes the byte and returns the key event it completed. The pending
+    /// sequence is dropped if the byte makes it invalid.
+    pub fn decode(&mut self, byte: u8) -> Result<Option<KeyEvent>, Error> {
+        self.buffer.push(byte);
+
+        match self.event() {
+            Ok(Some(event)) => {
+                self.buffer.clear();
+                Ok(Some(event))
+            }
+            Ok(None) => Ok(None),
+            Err(err) => {
+                self.buffer.clear();
+                Err(err)
+            }
+        }
+    }
+
+    fn event(&self) -> Result<Option<KeyEvent>, Error> {
+        let event = match self.buffer.as_slice() {
+            [SEP] | [ESC] | [US] | [US, _] | [SOH, ..] if self.buffer.len() < 5 => return Ok(None),
+            [ESC, PRO2] | [ESC, PRO2, RESP_STATUS | RESP_SPEED] => return Ok(None),
+            [ESC, PRO3] | [ESC, PRO3, ROUTING_RESPONSE] | [ESC, PRO3, ROUTING_RESPONSE, _] => {
+                return Ok(None)
+            }
+            [ESC, CSI] | [ESC, CSI, PRIVATE] => return Ok(None),
+
+            [SEP, code] => match FunctionKey::from_code([SEP, *code]) {
+                Some(key) => KeyEvent::Function(key),
+                None => KeyEvent::Protocol(Response::Acknowledgement(*code)),
+            },
+            [SS2, bytes @ ..] => match special_character(DisplayComponent::VGP5, bytes)? {
+                Some(char) => KeyEvent::Char(char),
+                None => return Ok(None),
+            },
+            [ESC, CSI, 0x41] => KeyEvent::Arrow(Direction::Up),
+            [ESC, CSI, 0x42] => KeyEvent::Arrow(Direction::Down),
+            [ESC, CSI, 0x43] => KeyEvent::Arrow(Direction::Right),
+            [ESC, CSI, 0x44] => KeyEvent::Arrow(Direction::Left),
+            [ESC, PRO2, RESP_STATUS, status] => {
+                KeyEvent::Protocol(Response::Status(Status::from_byte(*status)))
+            }
+            [ESC, PRO2, RESP_SPEED, speed] => {
+                KeyEvent::Protocol(Response::Speed(BaudRate::try_from(*speed)?))
+            }
+            [ESC, PRO3, ROUTING_RESPONSE, receiver, status] => {
+                match Module::from_receiver_code(*receiver) {
+                    Some(module) => {
+                        KeyEvent::Protocol(Response::Routing(module, *status & !STATUS_BYTE))
+                    }
+                    None => return Err(Error::UnexpectedSequence(self.buffer.clone())),
+                }
+            }
+            [SOH, manufacturer, model, version, EOT] => {
+                KeyEvent::Protocol(Response::Identification(Identification::from_bytes([
+                    *manufacturer,
+                    *model,
+                    *version,
+                ])))
+            }
+            [US, row, column] => KeyEvent::Protocol(Response::CursorPosition(
+                row.wrapping_sub(0x40),
+                column.wrapping_sub(0x40),
+            )),
+            [ESC, ..] => KeyEvent::Protocol(Response::Other(self.buffer.clone())),
+            [byte] if *byte < 0x80 => KeyEvent::Char(*byte as char),
+            _ => return Err(Error::UnexpectedSequence(self.buffer.clone())),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+/// Displays the keys received from a terminal in the context of its parser
+/// while the terminal echoes them on its own screen, so that the context
+/// stays in sync with what is actually displayed
+#[derive(Default)]
+pub struct LocalEcho {
+    decoder: InputDecoder,
+    sequence: Vec<u8>,
+}
+
+impl LocalEcho {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the bytes read from the terminal, the printable keys are
+    /// consumed by the parser if the keyboard is routed to the screen
+    pub fn feed(&mut self, parser: &mut Parser, bytes: &[u8]) {
+        for byte in bytes {
+            self.sequence.push(*byte);
+
+            match self.decoder.decode(*byte) {
+                Ok(None) => continue,
+                Ok(Some(KeyEvent::Char(char)))
+                    if !char.is_control() && parser.ctx().local_echo() =>
+                {
+                    //the terminal already displayed it, errors are not ours to report
+                    for byte in &self.sequence {
+                        let _ = parser.consume(*byte);
+                    }
+                }
+                _ => {}
+            }
+
+            self.sequence.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use teletel_protocol::codes::routing::{FROM_MODEM, KEYBOARD_EMITTER, SCREEN_RECEIVER};
+    use teletel_protocol::codes::teleinformatique::{TELEINFORMATIQUE_ACK, TELETEL_ACK};
+
+    fn decode_all(bytes: &[u8]) -> Vec<KeyEvent> {
+        let mut decoder = InputDecoder::new();
+
+        bytes
+            .iter()
+            .filter_map(|byte| decoder.decode(*byte).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_characters() {
+        assert_eq!(
+            decode_all(b"a\x19\x42e\x19\x4Bc\x19\x23"),
+            [
+                KeyEvent::Char('a'),
+                KeyEvent::Char('é'),
+                KeyEvent::Char('ç'),
+                KeyEvent::Char('£'),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_keys() {
+        let mut bytes = Vec::new();
+        bytes.extend(ENVOI);
+        bytes.extend(CONNEXION_FIN);
+        bytes.extend([ESC, CSI, 0x41, ESC, CSI, 0x44]);
+
+        assert_eq!(
+            decode_all(&bytes),
+            [
+                KeyEvent::Function(FunctionKey::Envoi),
+                KeyEvent::Function(FunctionKey::ConnexionFin),
+                KeyEvent::Arrow(Direction::Up),
+                KeyEvent::Arrow(Direction::Left),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_responses() {
+        let bytes = [
+            &[ESC, PRO2, RESP_STATUS, STATUS_BYTE | PAGE_MODE][..],
+            &[ESC, PRO2, RESP_SPEED, B1200],
+            &[ESC, PRO3, ROUTING_RESPONSE, SCREEN_RECEIVER, STATUS_BYTE | FROM_MODEM],
+            &TELETEL_ACK,
+            &[US, 0x41, 0x43],
+            &[SOH, b'C', b'v', b'1', EOT],
+            &TELEINFORMATIQUE_ACK,
+        ]
+        .concat();
+
+        assert_eq!(
+            decode_all(&bytes),
+            [
+                Response::Status(Status::from_byte(PAGE_MODE)),
+                Response::Speed(BaudRate::B1200),
+                Response::Routing(Module::Screen, FROM_MODEM),
+                Response::Acknowledgement(0x5E),
+                Response::CursorPosition(1, 3),
+                Response::Identification(Identification::from_bytes([b'C', b'v', b'1'])),
+                Response::Other(TELEINFORMATIQUE_ACK.to_vec()),
+            ]
+            .map(KeyEvent::Protocol)
+        );
+    }
+
+    #[test]
+    fn test_local_echo() {
+        let mut parser = Parser::new(DisplayComponent::VGP5);
+        let mut echo = LocalEcho::new();
+
+        echo.feed(
+            &mut parser,
+            &[b'a', SS2, 0x42, b'e', SEP, 0x41, ESC, PRO2, RESP_STATUS, STATUS_BYTE],
+        );
+        assert_eq!(parser.ctx().grid.cell(1, 1).content, 'a');
+        assert_eq!(parser.ctx().grid.cell(2, 1).content, 'é');
+        assert_eq!(parser.ctx().cursor_x, 3);
+
+        for byte in [ESC, PRO3, ROUTING_OFF, SCREEN_RECEIVER, KEYBOARD_EMITTER] {
+            parser.consume(byte).unwrap();
+        }
+
+        echo.feed(&mut parser, b"b");
+        assert_eq!(parser.ctx().cursor_x, 3);
+    }
+
+    #[test]
+    fn test_invalid_sequence_is_dropped() {
+        let mut decoder = InputDecoder::new();
+
+        assert_eq!(decoder.decode(SS2).unwrap(), None);
+        assert!(decoder.is_pending());
+        assert!(decoder.decode(0x20).is_err());
+        assert!(!decoder.is_pending());
+        assert_eq!(decoder.decode(b'z').unwrap(), Some(KeyEvent::Char('z')));
+    }
+}
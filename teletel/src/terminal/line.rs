@@ -0,0 +1,188 @@
+use crate::error::Error;
+use crate::protocol::{ProtocolExtension, SpeedAwareTerminal};
+use crate::terminal::{BaudRate, ReadableTerminal, WriteableTerminal};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Number of data bits of the characters
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum CharSize {
+    Bits7,
+    Bits8,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Parity {
+    None,
+    Odd,
+    Even,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum StopBits {
+    One,
+    Two,
+}
+
+/// Settings of the serial line of a terminal, the defaults matching a
+/// Minitel plugged through a USB to TTL adapter.
+///
+/// The framing is also sent to RFC 2217 serial servers while UARTs are
+/// configured by the application. The RTS and DTR lines are only driven on
+/// local serial ports.
+#[derive(Clone, Debug)]
+pub struct SerialConfig {
+    /// Read timeouts at 300, 1200, 4800 and 9600 bauds
+    timeouts: [Duration; 4],
+    pub(super) switch_delay: Duration,
+    pub(super) char_size: CharSize,
+    pub(super) parity: Parity,
+    pub(super) stop_bits: StopBits,
+    pub(super) rts: Option<bool>,
+    pub(super) dtr: Option<bool>,
+    pub(super) speeds: Vec<BaudRate>,
+    pub(super) attempts: usize,
+}
+
+impl Default for SerialConfig {
+    fn default() -> Self {
+        Self {
+            //arbitrary values selected by testing, may not work on all setups
+            timeouts: [
+                Duration::from_millis(500),
+                Duration::from_millis(180),
+                Duration::from_millis(48),
+                Duration::from_millis(24),
+            ],
+            //arbitrary value but required, maybe less works
+            switch_delay: Duration::from_secs(1),
+            char_size: CharSize::Bits7,
+            parity: Parity::Even,
+            stop_bits: StopBits::One,
+            rts: None,
+            dtr: None,
+            speeds: vec![
+                BaudRate::B1200,
+                BaudRate::B4800,
+                BaudRate::B300,
+                BaudRate::B9600,
+            ],
+            attempts: 3,
+        }
+    }
+}
+
+impl SerialConfig {
+    /// Changes how long reads wait for data at the given speed before
+    /// returning 0
+    pub fn with_read_timeout(mut self, baud_rate: BaudRate, timeout: Duration) -> Self {
+        self.timeouts[timeout_index(baud_rate)] = timeout;
+        self
+    }
+
+    /// Changes how long to wait for the terminal to switch to another
+    /// speed before switching the line, 1 second by default
+    pub fn with_switch_delay(mut self, delay: Duration) -> Self {
+        self.switch_delay = delay;
+        self
+    }
+
+    /// Overrides the 7E1 framing of the Minitel, for adapters or terminals
+    /// configured otherwise
+    pub fn with_framing(
+        mut self,
+        char_size: CharSize,
+        parity: Parity,
+        stop_bits: StopBits,
+    ) -> Self {
+        self.char_size = char_size;
+        self.parity = parity;
+        self.stop_bits = stop_bits;
+        self
+    }
+
+    /// Sets the RTS line once the port is opened, left untouched by default
+    pub fn with_rts(mut self, rts: bool) -> Self {
+        self.rts = Some(rts);
+        self
+    }
+
+    /// Sets the DTR line once the port is opened, left untouched by default
+    pub fn with_dtr(mut self, dtr: bool) -> Self {
+        self.dtr = Some(dtr);
+        self
+    }
+
+    /// Order in which the speeds are probed while looking for the speed of
+    /// the terminal, the speeds not supported by its profile are skipped.
+    /// 1200 bauds are probed first as Minitels start at this speed.
+    pub fn with_probing_order(mut self, speeds: impl Into<Vec<BaudRate>>) -> Self {
+        self.speeds = speeds.into();
+        self
+    }
+
+    /// Number of times every speed is probed before giving up on finding
+    /// the speed of the terminal, 3 by default
+    pub fn with_max_attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    pub fn read_timeout(&self, baud_rate: BaudRate) -> Duration {
+        self.timeouts[timeout_index(baud_rate)]
+    }
+}
+
+fn timeout_index(baud_rate: BaudRate) -> usize {
+    match baud_rate {
+        BaudRate::B300 => 0,
+        BaudRate::B1200 => 1,
+        BaudRate::B4800 => 2,
+        BaudRate::B9600 => 3,
+    }
+}
+
+/// Looks for the speed of the terminal by switching the line to the
+/// speeds of the probing order its profile supports until it answers, then
+/// raises it to the fastest one. Fails with [`Error::ConnectionFailure`]
+/// once every attempt was made.
+pub(super) fn match_connector_speed<T, F>(
+    term: &mut T,
+    config: &SerialConfig,
+    mut switch: F,
+) -> Result<(), Error>
+where
+    T: ReadableTerminal + WriteableTerminal + SpeedAwareTerminal,
+    F: FnMut(&mut T, BaudRate) -> Result<(), Error>,
+{
+    let profile = term.profile().unwrap_or_default();
+    let speeds: Vec<BaudRate> = config
+        .speeds
+        .iter()
+        .copied()
+        .filter(|speed| profile.supports_speed(*speed as u16))
+        .collect();
+
+    let fastest = speeds
+        .iter()
+        .copied()
+        .max_by_key(|speed| *speed as u16)
+        .unwrap_or(BaudRate::B1200);
+
+    for _ in 0..config.attempts {
+        for &baud_rate in &speeds {
+            switch(term, baud_rate)?;
+
+            if matches!(term.get_connector_speed(), Ok(claim) if claim == baud_rate) {
+                term.set_connector_speed(fastest)?;
+
+                if matches!(term.get_connector_speed(), Ok(speed) if speed == fastest) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    Err(Error::ConnectionFailure)
+}
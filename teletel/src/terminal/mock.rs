@@ -0,0 +1,44 @@
+use crate::terminal::BaudRate;
+use teletel_protocol::codes::{ESC, PRO1, PRO2, PROG, REQ_SPEED, RESET, RESP_SPEED};
+use teletel_protocol::codes::teleinformatique::TELETEL_ACK;
+
+/// Minitel plugged on a serial line, answering the speed and reset
+/// requests and switching its connector speed when asked to. The bytes
+/// sent at another speed than the one of the terminal are lost in both
+/// directions.
+pub struct MockMinitel {
+    /// Connector speed, as sent in the speed requests
+    pub speed: u8,
+    received: Vec<u8>,
+}
+
+impl MockMinitel {
+    pub fn new(speed: u8) -> Self {
+        Self {
+            speed,
+            received: Vec::new(),
+        }
+    }
+
+    /// Receives a byte sent on a line at the given speed in bauds,
+    /// returning the answer of the terminal
+    pub fn receive(&mut self, byte: u8, line: u32) -> Vec<u8> {
+        if !matches!(BaudRate::try_from(self.speed), Ok(speed) if speed as u32 == line) {
+            return Vec::new();
+        }
+
+        self.received.push(byte);
+        let answer = match self.received.as_slice() {
+            [.., ESC, PRO1, REQ_SPEED] => vec![ESC, PRO2, RESP_SPEED, self.speed],
+            [.., ESC, PRO1, RESET] => TELETEL_ACK.to_vec(),
+            [.., ESC, PRO2, PROG, speed] => {
+                self.speed = *speed;
+                Vec::new()
+            }
+            _ => return Vec::new(),
+        };
+
+        self.received.clear();
+        answer
+    }
+}
@@ -1,25 +1,88 @@
+#[cfg(feature = "async")]
+mod asynchronous;
 mod buffer;
 mod combinators;
+#[cfg(feature = "std")]
 mod file;
+mod input;
+#[cfg(any(
+    feature = "serial-terminal",
+    feature = "rfc2217-terminal",
+    feature = "uart-terminal"
+))]
+mod line;
+#[cfg(all(
+    test,
+    any(
+        all(target_os = "linux", feature = "pty-terminal", feature = "serial-terminal"),
+        feature = "rfc2217-terminal",
+        feature = "uart-terminal"
+    )
+))]
+mod mock;
+#[cfg(all(target_os = "linux", feature = "pty-terminal"))]
+mod pty;
+#[cfg(feature = "rfc2217-terminal")]
+mod rfc2217;
 #[cfg(feature = "serial-terminal")]
 mod serial;
 #[cfg(feature = "tcp-terminal")]
 mod tcp;
+#[cfg(feature = "telnet-terminal")]
+mod telnet;
+#[cfg(feature = "websocket-terminal")]
+mod websocket;
 mod to_terminal;
+#[cfg(feature = "uart-terminal")]
+mod uart;
 
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+#[cfg(feature = "std")]
 use std::io::ErrorKind;
+#[cfg(feature = "std")]
+use std::time::Instant;
 use crate::Error;
 use teletel_protocol::codes::*;
+use teletel_protocol::parser::Standard;
 
+#[cfg(feature = "async")]
+pub use asynchronous::{AsyncReadableTerminal, AsyncWriteableTerminal};
+#[cfg(feature = "async-serial-terminal")]
+pub use asynchronous::AsyncSerialTerminal;
+#[cfg(all(feature = "async", feature = "tcp-terminal"))]
+pub use asynchronous::AsyncTcpTerminal;
 pub use buffer::{Buffer, RawBuffer};
-pub use combinators::{Optional, Tap, Tee, pipe, bidirectional_pipe};
+pub use combinators::{Demultiplexer, Optional, Pce, Tap, Tee, pipe, bidirectional_pipe};
+#[cfg(feature = "std")]
 pub use file::FileReceiver;
+pub use input::{FunctionKey, InputDecoder, KeyEvent, LocalEcho, Response};
+#[cfg(any(
+    feature = "serial-terminal",
+    feature = "rfc2217-terminal",
+    feature = "uart-terminal"
+))]
+pub use line::{CharSize, Parity, SerialConfig, StopBits};
+#[cfg(all(target_os = "linux", feature = "pty-terminal"))]
+pub use pty::{PtyPair, PtyTerminal};
+#[cfg(feature = "rfc2217-terminal")]
+pub use rfc2217::Rfc2217Terminal;
 #[cfg(feature = "serial-terminal")]
 pub use serial::SerialTerminal;
 #[cfg(feature = "tcp-terminal")]
 pub use tcp::TcpTerminal;
-pub use teletel_protocol::parser::Context;
+#[cfg(feature = "telnet-terminal")]
+pub use telnet::TelnetTerminal;
+#[cfg(feature = "websocket-terminal")]
+pub use websocket::{WebSocketListener, WebSocketTerminal};
+pub use teletel_protocol::parser::{
+    Context, Feature, Identification, Manufacturer, Model, Module, Routing, Status, TerminalProfile,
+};
 pub use to_terminal::ToTerminal;
+#[cfg(feature = "uart-terminal")]
+pub use uart::UartTerminal;
 
 pub trait Contextualized {
     fn ctx(&self) -> &Context;
@@ -33,6 +96,7 @@ pub trait ReadableTerminal {
             match self.read(buf) {
                 Ok(0) => break,
                 Ok(n) => buf = &mut buf[n..],
+                #[cfg(feature = "std")]
                 Err(Error::Io(ref e)) if e.kind() == ErrorKind::Interrupted => {}
                 Err(e) => return Err(e),
             }
@@ -45,14 +109,50 @@ pub trait ReadableTerminal {
         }
     }
 
+    /// Drops the protocol responses waiting to be read, the keys typed by
+    /// the user are queued for [`ReadableTerminal::read_key`].
     fn discard(&mut self) ->  Result<(), Error> {
-        //todo: stop using this function, put the current buffer
-        // aside and just read the protocol sequences we need
-        self.read_to_vec()?;
+        let mut decoder = InputDecoder::new();
+
+        for byte in self.read_to_vec()? {
+            match (decoder.decode(byte), self.pending_keys()) {
+                (Ok(Some(KeyEvent::Protocol(_))), _) => {}
+                (Ok(Some(event)), Some(keys)) => keys.push_back(event),
+                _ => {}
+            }
+        }
 
         Ok(())
     }
 
+    /// Reads until the terminal sends a protocol response, the keys typed
+    /// in the meantime are queued for [`ReadableTerminal::read_key`].
+    fn read_response(&mut self) -> Result<Response, Error> {
+        let mut decoder = InputDecoder::new();
+        let mut byte = [0];
+
+        loop {
+            self.read_exact(&mut byte)?;
+
+            match decoder.decode(byte[0]) {
+                Ok(Some(KeyEvent::Protocol(response))) => return Ok(response),
+                Ok(Some(event)) => {
+                    if let Some(keys) = self.pending_keys() {
+                        keys.push_back(event);
+                    }
+                }
+                //invalid sequences are skipped, the decoder starts over
+                Ok(None) | Err(_) => {}
+            }
+        }
+    }
+
+    /// Keys read while waiting for a protocol response, returned first by
+    /// [`ReadableTerminal::read_key`]. Terminals without a queue drop them.
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        None
+    }
+
     fn read_to_vec(&mut self) -> Result<Vec<u8>, Error> {
         let mut data = Vec::new();
 
@@ -88,11 +188,87 @@ pub trait ReadableTerminal {
 
         Ok(data)
     }
+
+    /// Waits for the next key pressed on the keyboard, protocol responses
+    /// are returned as key events too. The timeout is checked between two
+    /// reads, terminals whose reads block only return once a byte arrives.
+    fn read_key(&mut self, timeout: Duration) -> Result<Option<KeyEvent>, Error> {
+        if let Some(event) = self.pending_keys().and_then(VecDeque::pop_front) {
+            return Ok(Some(event));
+        }
+
+        let mut decoder = InputDecoder::new();
+        let mut byte = [0];
+        #[cfg(feature = "std")]
+        let start = Instant::now();
+        //without std, the time is counted by the terminal in its waits
+        #[cfg(not(feature = "std"))]
+        let mut waited = Duration::ZERO;
+
+        loop {
+            #[cfg(feature = "std")]
+            let waited = start.elapsed();
+            if waited >= timeout {
+                break;
+            }
+
+            if self.read(&mut byte)? == 0 {
+                #[cfg(feature = "std")]
+                self.wait(Duration::from_millis(1));
+                #[cfg(not(feature = "std"))]
+                {
+                    waited += self.wait(Duration::from_millis(1));
+                }
+                continue;
+            }
+
+            if let Some(event) = decoder.decode(byte[0])? {
+                return Ok(Some(event));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Waits for the given duration, between two reads returning nothing
+    /// or for the terminal to process a request. Returns how long the
+    /// terminal waited since the previous call, the time spent waiting in
+    /// the reads included, which is used as a clock without std.
+    ///
+    /// Sleeps the current thread with std, terminals used without std have
+    /// to override it with their own delay.
+    fn wait(&mut self, duration: Duration) -> Duration {
+        #[cfg(feature = "std")]
+        std::thread::sleep(duration);
+
+        duration
+    }
 }
 
 pub trait WriteableTerminal {
     fn write(&mut self, buf: &[u8]) -> Result<(), Error>;
     fn flush(&mut self) -> Result<(), Error>;
+
+    /// Profile of the terminal if it is known, used to refuse
+    /// the features it does not support
+    fn profile(&self) -> Option<TerminalProfile> {
+        None
+    }
+
+    /// Standard the terminal follows if it is known, used to send the
+    /// sequences of the téléinformatique standard when the terminal is in
+    /// it
+    fn standard(&self) -> Option<Standard> {
+        None
+    }
+
+    /// Changes the profile the terminal validates the sent sequences
+    /// against, ignored by terminals without a parser
+    fn set_profile(&mut self, _profile: TerminalProfile) {}
+
+    /// Updates the context from a response read from the terminal,
+    /// ignored by terminals without a parser
+    fn observe(&mut self, _response: &[u8]) {}
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -101,10 +277,13 @@ pub enum BaudRate {
     B300 = 300,
     B1200 = 1200,
     B4800 = 4800,
-    #[cfg(feature = "minitel2")]
     B9600 = 9600,
 }
 
+impl BaudRate {
+    pub const ALL: [BaudRate; 4] = [BaudRate::B300, BaudRate::B1200, BaudRate::B4800, BaudRate::B9600];
+}
+
 impl TryFrom<u8> for BaudRate {
     type Error = Error;
 
@@ -113,7 +292,6 @@ impl TryFrom<u8> for BaudRate {
             B300 => Ok(BaudRate::B300),
             B1200 => Ok(BaudRate::B1200),
             B4800 => Ok(BaudRate::B4800),
-            #[cfg(feature = "minitel2")]
             B9600 => Ok(BaudRate::B9600),
             _ => Err(Error::UnexpectedSequence(vec![value])),
         }
@@ -127,7 +305,6 @@ impl ToTerminal for BaudRate {
             BaudRate::B300 => B300,
             BaudRate::B1200 => B1200,
             BaudRate::B4800 => B4800,
-            #[cfg(feature = "minitel2")]
             BaudRate::B9600 => B9600,
         }])
     }
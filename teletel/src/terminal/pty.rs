@@ -0,0 +1,203 @@
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, ToTerminal, WriteableTerminal,
+};
+use crate::Error;
+use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
+use nix::pty::openpty;
+use nix::sys::termios::{cfmakeraw, cfsetspeed, tcgetattr, tcsetattr, BaudRate, ControlFlags, SetArg};
+use nix::unistd::ttyname;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Error as IoError, Read, Write};
+use std::os::fd::AsFd;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use teletel_protocol::parser::{Parser, Standard, TerminalProfile};
+
+/// A terminal behind a Linux pseudo-terminal, its slave side being
+/// configured like the serial line of a Minitel when it is turned on.
+/// Software expecting a serial device, such as legacy videotex services or
+/// other emulators, can open the slave path and act as the Minitel.
+///
+/// Linux ignores the character size and the parity of pseudo-terminals,
+/// the line is always 8 bits without parity. The 8th bit of the received
+/// bytes is cleared as a 7 bits line would.
+pub struct PtyTerminal {
+    master: File,
+    /// Kept open so that reads do not fail until something opens the
+    /// slave side
+    _slave: File,
+    path: PathBuf,
+    timeout: Option<Duration>,
+    parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
+}
+
+impl PtyTerminal {
+    /// Creates a new pseudo-terminal pair.
+    pub fn open() -> Result<Self, Error> {
+        Self::with_profile(TerminalProfile::default())
+    }
+
+    /// Creates a new pseudo-terminal pair, the terminal opening the slave
+    /// side being described by the given profile.
+    pub fn with_profile(profile: TerminalProfile) -> Result<Self, Error> {
+        let pair = PtyPair::open()?;
+
+        Ok(Self {
+            master: pair.master,
+            _slave: pair.slave,
+            path: pair.path,
+            timeout: Some(Duration::from_millis(100)),
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
+        })
+    }
+
+    /// Path of the device to open as the serial port of the Minitel
+    pub fn slave_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Changes how long reads wait for data before returning 0, 100ms by
+    /// default. Without timeout, reads block until data arrives.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    #[inline(always)]
+    pub fn send(&mut self, data: impl ToTerminal) -> Result<(), Error> {
+        data.to_terminal(self)
+    }
+}
+
+impl ReadableTerminal for PtyTerminal {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if let Some(timeout) = self.timeout {
+            let mut fds = [PollFd::new(self.master.as_fd(), PollFlags::POLLIN)];
+            let timeout = PollTimeout::try_from(timeout).unwrap_or(PollTimeout::MAX);
+
+            if poll(&mut fds, timeout).map_err(IoError::from)? == 0 {
+                return Ok(0);
+            }
+        }
+
+        let bytes_read = self.master.read(buf)?;
+        for byte in &mut buf[..bytes_read] {
+            *byte &= 0x7F;
+        }
+
+        self.echo.feed(&mut self.parser, &buf[..bytes_read]);
+
+        Ok(bytes_read)
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
+}
+
+impl WriteableTerminal for PtyTerminal {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for i in 0..buf.len() {
+            self.parser.consume(buf[i])?;
+            self.master.write_all(&buf[i..i + 1])?;
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> Result<(), Error> {
+        self.master.flush().map_err(Into::into)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl Contextualized for PtyTerminal {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
+
+/// Pseudo-terminal pair whose line is configured like the serial line of
+/// a Minitel when it is turned on: raw mode at 1200 bauds with one stop bit
+/// and without flow control
+pub struct PtyPair {
+    pub master: File,
+    /// Kept open so that reads on the master side do not fail until
+    /// something opens the slave path
+    pub slave: File,
+    /// Path of the slave side
+    pub path: PathBuf,
+}
+
+impl PtyPair {
+    /// Creates a new pseudo-terminal pair.
+    pub fn open() -> Result<Self, Error> {
+        let pty = openpty(None, None).map_err(IoError::from)?;
+        let path = ttyname(&pty.slave).map_err(IoError::from)?;
+
+        let mut termios = tcgetattr(&pty.master).map_err(IoError::from)?;
+        cfmakeraw(&mut termios);
+        termios.control_flags &= !(ControlFlags::CSTOPB | ControlFlags::CRTSCTS);
+        termios.control_flags |= ControlFlags::CREAD | ControlFlags::CLOCAL;
+        cfsetspeed(&mut termios, BaudRate::B1200).map_err(IoError::from)?;
+        tcsetattr(&pty.master, SetArg::TCSANOW, &termios).map_err(IoError::from)?;
+
+        Ok(Self {
+            master: File::from(pty.master),
+            slave: File::from(pty.slave),
+            path,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pty_terminal() {
+        let mut term = PtyTerminal::open().unwrap();
+        let mut minitel = File::options()
+            .read(true)
+            .write(true)
+            .open(term.slave_path())
+            .unwrap();
+
+        term.send("Bonjour").unwrap();
+        term.flush().unwrap();
+
+        let mut received = [0; 7];
+        minitel.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"Bonjour");
+
+        minitel.write_all(b"3615").unwrap();
+
+        let mut buf = [0; 4];
+        term.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"3615");
+        assert_eq!(term.ctx().grid.cell(8, 1).content, '3');
+
+        term.set_read_timeout(Some(Duration::from_millis(10)));
+        assert_eq!(term.read(&mut buf).unwrap(), 0);
+    }
+}
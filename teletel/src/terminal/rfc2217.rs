@@ -0,0 +1,455 @@
+use crate::error::Error;
+use crate::protocol::{ProtocolExtension, SpeedAwareTerminal};
+use crate::terminal::line::{self, CharSize, Parity, SerialConfig, StopBits};
+use crate::terminal::telnet::{Telnet, BINARY, IAC, SB, SE, SUPPRESS_GO_AHEAD};
+use crate::terminal::{
+    BaudRate, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, ToTerminal, WriteableTerminal,
+};
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use teletel_protocol::codes::FF;
+use teletel_protocol::parser::{Context, Parser, Standard, TerminalProfile};
+
+const COM_PORT_OPTION: u8 = 0x2C;
+
+const SET_BAUDRATE: u8 = 1;
+const SET_DATASIZE: u8 = 2;
+const SET_PARITY: u8 = 3;
+const SET_STOPSIZE: u8 = 4;
+const SET_CONTROL: u8 = 5;
+/// Added to the commands by the server to acknowledge them
+const SERVER_OFFSET: u8 = 100;
+
+const PARITY_NONE: u8 = 1;
+const PARITY_ODD: u8 = 2;
+const PARITY_EVEN: u8 = 3;
+const STOPSIZE_ONE: u8 = 1;
+const STOPSIZE_TWO: u8 = 2;
+const CONTROL_NONE: u8 = 1;
+
+const LOCAL_OPTIONS: [u8; 3] = [BINARY, SUPPRESS_GO_AHEAD, COM_PORT_OPTION];
+const REMOTE_OPTIONS: [u8; 2] = [BINARY, SUPPRESS_GO_AHEAD];
+
+/// A terminal plugged into a serial server such as ser2net, the framing
+/// and the baud rate changes being sent to the server through the telnet
+/// COM port option (RFC 2217).
+pub struct Rfc2217Terminal {
+    stream: TcpStream,
+    baud_rate: BaudRate,
+    config: SerialConfig,
+    parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
+    telnet: Telnet,
+}
+
+impl Rfc2217Terminal {
+    pub fn new<A: ToSocketAddrs>(addr: A, baud_rate: Option<BaudRate>) -> Result<Self, Error> {
+        Self::with_profile(addr, baud_rate, TerminalProfile::default())
+    }
+
+    /// Connects to the serial server of a terminal described by the given
+    /// profile, the connector speed is raised to the fastest speed of the
+    /// profile if no baud rate is given.
+    pub fn with_profile<A: ToSocketAddrs>(
+        addr: A,
+        baud_rate: Option<BaudRate>,
+        profile: TerminalProfile,
+    ) -> Result<Self, Error> {
+        Self::with_config(addr, baud_rate, profile, SerialConfig::default())
+    }
+
+    /// Connects to the serial server of a terminal described by the given
+    /// profile with the given line settings, the connector speed is raised
+    /// to the fastest speed of the profile if no baud rate is given.
+    pub fn with_config<A: ToSocketAddrs>(
+        addr: A,
+        baud_rate: Option<BaudRate>,
+        profile: TerminalProfile,
+        config: SerialConfig,
+    ) -> Result<Self, Error> {
+        let mut stream = TcpStream::connect(addr).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("could not connect: {e}"),
+            ))
+        })?;
+        stream.set_nodelay(true)?;
+
+        let mut telnet = Telnet::new(&LOCAL_OPTIONS, &REMOTE_OPTIONS);
+        stream.write_all(&telnet.negotiate())?;
+
+        let mut term = Rfc2217Terminal {
+            stream,
+            baud_rate: baud_rate.unwrap_or(BaudRate::B1200),
+            config,
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
+            telnet,
+        };
+
+        term.configure(term.baud_rate)?;
+
+        if baud_rate.is_none() {
+            term.match_connector_speed()?;
+        }
+
+        Ok(term)
+    }
+
+    /// Speed of the serial port of the server
+    pub fn baud_rate(&self) -> BaudRate {
+        self.baud_rate
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.stream.peer_addr().map_err(Into::into)
+    }
+
+    /// Sets up the serial port of the server and waits for the baud rate
+    /// to be acknowledged, the data received in the meantime is dropped
+    fn configure(&mut self, baud_rate: BaudRate) -> Result<(), Error> {
+        let mut request = Vec::new();
+        let baud = (baud_rate as u32).to_be_bytes();
+        let datasize = match self.config.char_size {
+            CharSize::Bits7 => 7,
+            CharSize::Bits8 => 8,
+        };
+        let parity = match self.config.parity {
+            Parity::None => PARITY_NONE,
+            Parity::Odd => PARITY_ODD,
+            Parity::Even => PARITY_EVEN,
+        };
+        let stopsize = match self.config.stop_bits {
+            StopBits::One => STOPSIZE_ONE,
+            StopBits::Two => STOPSIZE_TWO,
+        };
+
+        for (command, value) in [
+            (SET_BAUDRATE, &baud[..]),
+            (SET_DATASIZE, &[datasize]),
+            (SET_PARITY, &[parity]),
+            (SET_STOPSIZE, &[stopsize]),
+            (SET_CONTROL, &[CONTROL_NONE]),
+        ] {
+            request.extend([IAC, SB, COM_PORT_OPTION, command]);
+            for byte in value {
+                if *byte == IAC {
+                    request.push(IAC);
+                }
+
+                request.push(*byte);
+            }
+            request.extend([IAC, SE]);
+        }
+
+        self.stream.write_all(&request)?;
+        self.stream.flush()?;
+
+        //the serial port is slower than the network, the timeouts of the
+        //serial terminal are enough
+        self.stream
+            .set_read_timeout(Some(self.config.read_timeout(baud_rate)))?;
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut buffer = [0; 64];
+        while Instant::now() < deadline {
+            self.receive(&mut buffer)?;
+
+            let acknowledged = self.telnet.subnegotiations.drain(..).any(|subnegotiation| {
+                matches!(
+                    subnegotiation.as_slice(),
+                    [COM_PORT_OPTION, command, ..] if *command == SET_BAUDRATE + SERVER_OFFSET
+                )
+            });
+
+            if acknowledged {
+                return Ok(());
+            }
+        }
+
+        Err(Error::Io(std::io::Error::new(
+            ErrorKind::TimedOut,
+            "the serial server did not acknowledge the baud rate",
+        )))
+    }
+
+    /// Reads from the server, strips the telnet commands and answers them.
+    /// Chunks holding only commands are skipped until data or a
+    /// subnegotiation arrives, 0 is returned once the read times out.
+    fn receive(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        loop {
+            let bytes_read = match self.stream.read(buf) {
+                Ok(0) if !buf.is_empty() => return Err(Error::Disconnected),
+                Ok(bytes_read) => bytes_read,
+                Err(ref e) if e.kind() == ErrorKind::TimedOut => return Ok(0),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => return Ok(0),
+                Err(e) => return Err(Error::Io(e)),
+            };
+
+            let mut count = 0;
+            for i in 0..bytes_read {
+                if let Some(byte) = self.telnet.decode(buf[i]) {
+                    buf[count] = byte;
+                    count += 1;
+                }
+            }
+
+            if !self.telnet.replies.is_empty() {
+                self.stream.write_all(&self.telnet.replies)?;
+                self.telnet.replies.clear();
+            }
+
+            if count > 0 || bytes_read == 0 || !self.telnet.subnegotiations.is_empty() {
+                return Ok(count);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn send(&mut self, data: impl ToTerminal) -> Result<(), Error> {
+        data.to_terminal(self)
+    }
+}
+
+impl ReadableTerminal for Rfc2217Terminal {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        loop {
+            let count = self.receive(buf)?;
+
+            //only the acknowledgements of the configuration are expected,
+            //the notifications of the server are skipped
+            let notified = !self.telnet.subnegotiations.is_empty();
+            self.telnet.subnegotiations.clear();
+
+            if count > 0 || !notified {
+                self.echo.feed(&mut self.parser, &buf[..count]);
+
+                return Ok(count);
+            }
+        }
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
+}
+
+impl WriteableTerminal for Rfc2217Terminal {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for byte in buf {
+            self.parser.consume(*byte)?;
+
+            if *byte == IAC {
+                self.stream.write_all(&[IAC, IAC])?;
+            } else {
+                self.stream.write_all(&[*byte])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> Result<(), Error> {
+        self.stream.flush().map_err(Into::into)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl Contextualized for Rfc2217Terminal {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
+
+impl SpeedAwareTerminal for Rfc2217Terminal {
+    /// Probes the speeds in the order of the configuration until the
+    /// terminal answers, fails with [`Error::ConnectionFailure`] once every
+    /// attempt was made
+    fn match_connector_speed(&mut self) -> Result<(), Error> {
+        let config = self.config.clone();
+
+        line::match_connector_speed(self, &config, |term, baud_rate| {
+            if term.baud_rate != baud_rate {
+                term.configure(baud_rate)?;
+                term.baud_rate = baud_rate;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn set_connector_speed(&mut self, baud_rate: BaudRate) -> Result<(), Error> {
+        if !self.parser.profile().supports_speed(baud_rate as u16) {
+            return Err(Error::UnsupportedSpeed(baud_rate));
+        }
+
+        self.read_to_vec()?;
+        baud_rate.to_terminal(self)?;
+        self.flush()?;
+
+        //the terminal needs some time to switch, as on a local port
+        std::thread::sleep(self.config.switch_delay);
+        self.configure(baud_rate)?;
+        self.baud_rate = baud_rate;
+
+        self.reset()?;
+        self.send(FF)?;
+        self.flush()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::mock::MockMinitel;
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::thread;
+    use teletel_protocol::codes::{B4800, B9600, ESC, PRO1, PRO2, REQ_SPEED, RESP_SPEED};
+
+    const NOTIFY_MODEMSTATE: u8 = SERVER_OFFSET + 7;
+
+    /// Serial server with a Minitel plugged at 4800 bauds, reporting the
+    /// speed of its port after each change. The modem state is notified
+    /// before every answer if asked to.
+    fn serial_server(notify: bool) -> (SocketAddr, Receiver<u32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut telnet = Telnet::new(&REMOTE_OPTIONS, &LOCAL_OPTIONS);
+
+            let mut port = 9600;
+            let mut minitel = MockMinitel::new(B4800);
+            let mut buffer = [0; 64];
+
+            while let Ok(bytes_read) = stream.read(&mut buffer) {
+                if bytes_read == 0 {
+                    break;
+                }
+
+                let mut answer = Vec::new();
+                for byte in &buffer[..bytes_read] {
+                    if let Some(byte) = telnet.decode(*byte) {
+                        answer.extend(minitel.receive(byte, port));
+                    }
+                }
+
+                answer.append(&mut telnet.replies);
+                for subnegotiation in telnet.subnegotiations.drain(..) {
+                    if let [COM_PORT_OPTION, SET_BAUDRATE, baud @ ..] = subnegotiation.as_slice() {
+                        port = u32::from_be_bytes(baud.try_into().unwrap());
+                        tx.send(port).unwrap();
+                    }
+
+                    answer.extend([IAC, SB, COM_PORT_OPTION, subnegotiation[1] + SERVER_OFFSET]);
+                    answer.extend(&subnegotiation[2..]);
+                    answer.extend([IAC, SE]);
+                }
+
+                //the modem state notifications come in their own chunks
+                if notify && !answer.is_empty() {
+                    stream
+                        .write_all(&[IAC, SB, COM_PORT_OPTION, NOTIFY_MODEMSTATE, 0x30, IAC, SE])
+                        .unwrap();
+                    stream.flush().unwrap();
+                    thread::sleep(Duration::from_millis(10));
+                }
+
+                stream.write_all(&answer).unwrap();
+            }
+        });
+
+        (addr, rx)
+    }
+
+    #[test]
+    fn test_match_connector_speed() {
+        let (addr, ports) = serial_server(false);
+
+        let mut term =
+            Rfc2217Terminal::with_profile(addr, None, TerminalProfile::MINITEL_2).unwrap();
+        assert_eq!(term.baud_rate(), BaudRate::B9600);
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B9600);
+        assert_eq!(ports.try_iter().collect::<Vec<_>>(), [1200, 4800, 9600]);
+
+        term.write(&[ESC, PRO1, REQ_SPEED]).unwrap();
+        term.flush().unwrap();
+
+        let mut answer = [0; 4];
+        term.read_exact(&mut answer).unwrap();
+        assert_eq!(answer, [ESC, PRO2, RESP_SPEED, B9600]);
+    }
+
+    #[test]
+    fn test_unsupported_speed() {
+        let (addr, ports) = serial_server(false);
+
+        let mut term =
+            Rfc2217Terminal::with_profile(addr, Some(BaudRate::B4800), TerminalProfile::MINITEL_1B)
+                .unwrap();
+        assert_eq!(ports.recv().unwrap(), 4800);
+
+        assert!(matches!(
+            term.set_connector_speed(BaudRate::B9600),
+            Err(Error::UnsupportedSpeed(BaudRate::B9600))
+        ));
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B4800);
+    }
+
+    #[test]
+    fn test_connection_failure() {
+        let (addr, _ports) = serial_server(false);
+        let config = SerialConfig::default()
+            .with_probing_order([BaudRate::B300, BaudRate::B1200])
+            .with_max_attempts(2);
+
+        assert!(matches!(
+            Rfc2217Terminal::with_config(addr, None, TerminalProfile::MINITEL_2, config),
+            Err(Error::ConnectionFailure)
+        ));
+
+        let (addr, _ports) = serial_server(false);
+        let config = SerialConfig::default().with_probing_order([]);
+
+        assert!(matches!(
+            Rfc2217Terminal::with_config(addr, None, TerminalProfile::MINITEL_2, config),
+            Err(Error::ConnectionFailure)
+        ));
+    }
+
+    #[test]
+    fn test_notifications_are_skipped() {
+        let (addr, ports) = serial_server(true);
+
+        let mut term =
+            Rfc2217Terminal::with_profile(addr, Some(BaudRate::B4800), TerminalProfile::MINITEL_1B)
+                .unwrap();
+        assert_eq!(ports.recv().unwrap(), 4800);
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B4800);
+    }
+}
@@ -1,21 +1,50 @@
 use crate::error::Error;
 use crate::protocol::{ProtocolExtension, SpeedAwareTerminal};
-use crate::terminal::{BaudRate, Contextualized, ReadableTerminal, ToTerminal, WriteableTerminal};
-use serial2::{CharSize, FlowControl, Parity, SerialPort, Settings, StopBits};
+use crate::terminal::{
+    BaudRate, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, ToTerminal, WriteableTerminal,
+};
+use crate::terminal::line::{self, CharSize, Parity, SerialConfig, StopBits};
+use serial2::{FlowControl, SerialPort, Settings};
+use std::collections::VecDeque;
 use std::io::ErrorKind;
-use std::time::Duration;
 use teletel_protocol::codes::{FF, PRO2, PROG};
-use teletel_protocol::parser::{Context, DisplayComponent, Parser};
+use teletel_protocol::parser::{Context, Parser, Standard, TerminalProfile};
 
 pub struct SerialTerminal {
     path: String,
     baud_rate: BaudRate,
     port: SerialPort,
+    config: SerialConfig,
     parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
 }
 
 impl SerialTerminal {
     pub fn new<S: AsRef<str>>(path: S, baud_rate: Option<BaudRate>) -> Result<Self, Error> {
+        Self::with_profile(path, baud_rate, TerminalProfile::default())
+    }
+
+    /// Opens the serial port of a terminal described by the given profile,
+    /// the connector speed is raised to the fastest speed of the profile
+    /// if no baud rate is given.
+    pub fn with_profile<S: AsRef<str>>(
+        path: S,
+        baud_rate: Option<BaudRate>,
+        profile: TerminalProfile,
+    ) -> Result<Self, Error> {
+        Self::with_config(path, baud_rate, profile, SerialConfig::default())
+    }
+
+    /// Opens the serial port of a terminal described by the given profile
+    /// with the given line settings, the connector speed is raised to the
+    /// fastest speed of the profile if no baud rate is given.
+    pub fn with_config<S: AsRef<str>>(
+        path: S,
+        baud_rate: Option<BaudRate>,
+        profile: TerminalProfile,
+        config: SerialConfig,
+    ) -> Result<Self, Error> {
         let default_baud_rate = if let Some(baud_rate) = baud_rate {
             baud_rate
         } else {
@@ -25,8 +54,11 @@ impl SerialTerminal {
         let mut term = SerialTerminal {
             path: path.as_ref().to_owned(),
             baud_rate: default_baud_rate,
-            port: SerialTerminal::connect(path, default_baud_rate)?,
-            parser: Parser::new(DisplayComponent::VGP5),
+            port: SerialTerminal::connect(path, default_baud_rate, &config)?,
+            config,
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
         };
 
         if baud_rate.is_none() {
@@ -36,27 +68,73 @@ impl SerialTerminal {
         Ok(term)
     }
 
-    fn connect<S: AsRef<str>>(path: S, baud_rate: BaudRate) -> Result<SerialPort, Error> {
+    /// Lists the serial ports a Minitel answered on along with the speed
+    /// of its connector. The ports that cannot be opened, such as the ones
+    /// used by another program, are skipped.
+    pub fn discover() -> Result<Vec<(String, BaudRate)>, Error> {
+        Self::discover_with(&SerialConfig::default())
+    }
+
+    /// Lists the serial ports a Minitel answered on with the given line
+    /// settings along with the speed of its connector
+    pub fn discover_with(config: &SerialConfig) -> Result<Vec<(String, BaudRate)>, Error> {
+        let mut found = Vec::new();
+
+        for path in SerialPort::available_ports()? {
+            let path = path.to_string_lossy().into_owned();
+            if let Ok(Some(baud_rate)) = SerialTerminal::probe(&path, config) {
+                found.push((path, baud_rate));
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Asks for the connector speed at every speed of the probing order of
+    /// the configuration, returning the speed the terminal answered at if
+    /// a Minitel is plugged on the port.
+    pub fn probe<S: AsRef<str>>(path: S, config: &SerialConfig) -> Result<Option<BaudRate>, Error> {
+        for &baud_rate in &config.speeds {
+            let mut term = SerialTerminal::with_config(
+                &path,
+                Some(baud_rate),
+                TerminalProfile::default(),
+                config.clone(),
+            )?;
+
+            if matches!(term.get_connector_speed(), Ok(speed) if speed == baud_rate) {
+                return Ok(Some(baud_rate));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn connect<S: AsRef<str>>(
+        path: S,
+        baud_rate: BaudRate,
+        config: &SerialConfig,
+    ) -> Result<SerialPort, Error> {
         let mut port = SerialPort::open(path.as_ref(), |mut settings: Settings| {
             settings.set_raw();
             settings.set_baud_rate(baud_rate as u32)?;
-            settings.set_char_size(CharSize::Bits7);
-            settings.set_parity(Parity::Even);
-            settings.set_stop_bits(StopBits::One);
+            settings.set_char_size(config.char_size.into());
+            settings.set_parity(config.parity.into());
+            settings.set_stop_bits(config.stop_bits.into());
             settings.set_flow_control(FlowControl::None);
 
             Ok(settings)
         })?;
 
-        //arbitrary values selected by testing, may not work on all setups
-        //todo: allow manually setting it ?
-        port.set_read_timeout(Duration::from_millis(match baud_rate {
-            BaudRate::B300 => 500,
-            BaudRate::B1200 => 180,
-            BaudRate::B4800 => 48,
-            #[cfg(feature = "minitel2")]
-            BaudRate::B9600 => 24,
-        }))?;
+        port.set_read_timeout(config.read_timeout(baud_rate))?;
+
+        if let Some(rts) = config.rts {
+            port.set_rts(rts)?;
+        }
+
+        if let Some(dtr) = config.dtr {
+            port.set_dtr(dtr)?;
+        }
 
         Ok(port)
     }
@@ -67,14 +145,56 @@ impl SerialTerminal {
     }
 }
 
+impl From<CharSize> for serial2::CharSize {
+    fn from(char_size: CharSize) -> Self {
+        match char_size {
+            CharSize::Bits7 => serial2::CharSize::Bits7,
+            CharSize::Bits8 => serial2::CharSize::Bits8,
+        }
+    }
+}
+
+impl From<Parity> for serial2::Parity {
+    fn from(parity: Parity) -> Self {
+        match parity {
+            Parity::None => serial2::Parity::None,
+            Parity::Odd => serial2::Parity::Odd,
+            Parity::Even => serial2::Parity::Even,
+        }
+    }
+}
+
+impl From<StopBits> for serial2::StopBits {
+    fn from(stop_bits: StopBits) -> Self {
+        match stop_bits {
+            StopBits::One => serial2::StopBits::One,
+            StopBits::Two => serial2::StopBits::Two,
+        }
+    }
+}
+
 impl ReadableTerminal for SerialTerminal {
     fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
         match self.port.read(buffer) {
-            Ok(bytes_read) => Ok(bytes_read),
+            Ok(bytes_read) => {
+                //the parity bit is not always cleared by the adapters
+                if self.config.char_size == CharSize::Bits7 {
+                    for byte in &mut buffer[..bytes_read] {
+                        *byte &= 0x7F;
+                    }
+                }
+
+                self.echo.feed(&mut self.parser, &buffer[..bytes_read]);
+                Ok(bytes_read)
+            }
             Err(ref e) if e.kind() == ErrorKind::TimedOut => Ok(0),
             Err(e) => Err(Error::Io(e)),
         }
     }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
 }
 
 impl WriteableTerminal for SerialTerminal {
@@ -91,6 +211,22 @@ impl WriteableTerminal for SerialTerminal {
     fn flush(&mut self) -> Result<(), Error> {
         self.port.flush().map_err(Into::into)
     }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
 }
 
 impl Contextualized for SerialTerminal {
@@ -100,48 +236,34 @@ impl Contextualized for SerialTerminal {
 }
 
 impl SpeedAwareTerminal for SerialTerminal {
+    /// Probes the speeds in the order of the configuration until the
+    /// terminal answers, fails with [`Error::ConnectionFailure`] once every
+    /// attempt was made
     fn match_connector_speed(&mut self) -> Result<(), Error> {
-        let speeds = [
-            BaudRate::B1200,
-            BaudRate::B4800,
-            BaudRate::B300,
-            #[cfg(feature = "minitel2")]
-            BaudRate::B9600,
-        ];
-
-        let mut i = 0;
-        loop {
-            let baud_rate = speeds[i % speeds.len()];
-            i += 1;
-
-            if self.port.get_configuration()?.get_baud_rate()? != baud_rate as u32 {
-                self.port = SerialTerminal::connect(&self.path, baud_rate)?;
-            }
+        let config = self.config.clone();
 
-            if matches!(self.get_connector_speed(), Ok(claim) if claim == baud_rate) {
-                #[cfg(feature = "minitel2")]
-                self.set_connector_speed(BaudRate::B9600)?;
-
-                #[cfg(not(feature = "minitel2"))]
-                self.set_connector_speed(BaudRate::B4800)?;
-
-                match self.get_connector_speed() {
-                    Ok(speed) if speed == self.baud_rate => return Ok(()),
-                    _ => continue,
-                }
+        line::match_connector_speed(self, &config, |term, baud_rate| {
+            if term.port.get_configuration()?.get_baud_rate()? != baud_rate as u32 {
+                term.port = SerialTerminal::connect(&term.path, baud_rate, &term.config)?;
+                term.baud_rate = baud_rate;
             }
-        }
+
+            Ok(())
+        })
     }
 
     fn set_connector_speed(&mut self, baud_rate: BaudRate) -> Result<(), Error> {
+        if !self.parser.profile().supports_speed(baud_rate as u16) {
+            return Err(Error::UnsupportedSpeed(baud_rate));
+        }
+
         self.read_to_vec()?;
         self.write(&[PRO2, PROG])?;
         baud_rate.to_terminal(self)?;
         self.flush()?;
 
-        //arbitrary value but required, maybe less works
-        std::thread::sleep(Duration::from_secs(1));
-        self.port = SerialTerminal::connect(&self.path, baud_rate)?;
+        std::thread::sleep(self.config.switch_delay);
+        self.port = SerialTerminal::connect(&self.path, baud_rate, &self.config)?;
         self.baud_rate = baud_rate;
 
         self.reset()?;
@@ -151,3 +273,121 @@ impl SpeedAwareTerminal for SerialTerminal {
         Ok(())
     }
 }
+
+#[cfg(all(test, target_os = "linux", feature = "pty-terminal"))]
+mod tests {
+    use super::*;
+    use crate::terminal::mock::MockMinitel;
+    use crate::terminal::{PtyPair, PtyTerminal};
+    use serial2::KeepSettings;
+    use std::fs::File;
+    use std::io::{Read, Write};
+    use std::path::PathBuf;
+    use std::thread;
+    use std::time::Duration;
+    use teletel_protocol::codes::B4800;
+
+    /// Speed the slave side was set to by the serial terminal
+    fn line_speed(slave: &SerialPort) -> u32 {
+        slave.get_configuration().unwrap().get_baud_rate().unwrap()
+    }
+
+    /// Plugs a Minitel at the given speed on a pseudo-terminal, the slave
+    /// side is kept open while the serial terminal reconnects
+    fn plug_minitel(speed: u8) -> (PathBuf, File) {
+        let PtyPair { mut master, slave, path } = PtyPair::open().unwrap();
+        let line = SerialPort::open(&path, KeepSettings).unwrap();
+
+        thread::spawn(move || {
+            let mut minitel = MockMinitel::new(speed);
+            let mut buffer = [0; 64];
+
+            while let Ok(bytes_read) = master.read(&mut buffer) {
+                for byte in &buffer[..bytes_read] {
+                    let answer = minitel.receive(*byte, line_speed(&line));
+                    master.write_all(&answer).unwrap();
+                }
+            }
+        });
+
+        (path, slave)
+    }
+
+    /// Pseudo-terminals only handle 8 bits without parity
+    fn pty_config() -> SerialConfig {
+        SerialConfig::default().with_framing(CharSize::Bits8, Parity::None, StopBits::One)
+    }
+
+    #[test]
+    fn test_match_connector_speed() {
+        let (path, slave) = plug_minitel(B4800);
+
+        let mut term = SerialTerminal::with_config(
+            path.to_str().unwrap(),
+            None,
+            TerminalProfile::MINITEL_1B,
+            pty_config(),
+        )
+        .unwrap();
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B4800);
+
+        assert!(matches!(
+            term.set_connector_speed(BaudRate::B9600),
+            Err(Error::UnsupportedSpeed(BaudRate::B9600))
+        ));
+
+        drop((term, slave));
+    }
+
+    #[test]
+    fn test_probe() {
+        let (path, slave) = plug_minitel(B4800);
+        let config = pty_config();
+
+        assert_eq!(
+            SerialTerminal::probe(path.to_str().unwrap(), &config).unwrap(),
+            Some(BaudRate::B4800)
+        );
+
+        let term = PtyTerminal::open().unwrap();
+        let config = config.with_probing_order([BaudRate::B1200, BaudRate::B9600]);
+        assert_eq!(
+            SerialTerminal::probe(term.slave_path().to_str().unwrap(), &config).unwrap(),
+            None
+        );
+
+        drop(slave);
+    }
+
+    #[test]
+    fn test_without_minitel() {
+        let term = PtyTerminal::open().unwrap();
+        let config = pty_config()
+            .with_read_timeout(BaudRate::B1200, Duration::from_millis(10))
+            .with_read_timeout(BaudRate::B4800, Duration::from_millis(10))
+            .with_max_attempts(1);
+
+        //nothing answers on the master side
+        let result = SerialTerminal::with_config(
+            term.slave_path().to_str().unwrap(),
+            None,
+            TerminalProfile::MINITEL_1B,
+            config,
+        );
+        assert!(matches!(result, Err(Error::ConnectionFailure)));
+    }
+
+    #[test]
+    fn test_framing_is_not_forced() {
+        let term = PtyTerminal::open().unwrap();
+
+        //the default 7E1 framing cannot be applied to a pseudo-terminal
+        assert!(SerialTerminal::with_config(
+            term.slave_path().to_str().unwrap(),
+            Some(BaudRate::B1200),
+            TerminalProfile::MINITEL_1B,
+            SerialConfig::default(),
+        )
+        .is_err());
+    }
+}
@@ -1,13 +1,19 @@
-use crate::terminal::{Context, Contextualized, ReadableTerminal, ToTerminal, WriteableTerminal};
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, ToTerminal, WriteableTerminal,
+};
 use crate::Error;
+use std::collections::VecDeque;
 use std::io::{ErrorKind, Read, Write};
-use std::net::TcpStream;
-use teletel_protocol::parser::{DisplayComponent, Parser};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+use teletel_protocol::parser::{Parser, Standard, TerminalProfile};
 
 /// A terminal that communicates over TCP.
 pub struct TcpTerminal {
     stream: TcpStream,
     parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
 }
 
 impl TcpTerminal {
@@ -18,6 +24,15 @@ impl TcpTerminal {
 
     /// Connect to a specific address.
     pub fn connect_to<A: std::net::ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        Self::connect_with_profile(addr, TerminalProfile::default())
+    }
+
+    /// Connect to a specific address, the remote terminal being described
+    /// by the given profile.
+    pub fn connect_with_profile<A: std::net::ToSocketAddrs>(
+        addr: A,
+        profile: TerminalProfile,
+    ) -> Result<Self, Error> {
         let stream = TcpStream::connect(addr).map_err(|e| {
             Error::Io(std::io::Error::new(
                 e.kind(),
@@ -25,10 +40,27 @@ impl TcpTerminal {
             ))
         })?;
 
-        Ok(Self {
+        Ok(Self::from_stream(stream, profile))
+    }
+
+    /// Wraps an established connection, such as one accepted by a server.
+    pub fn from_stream(stream: TcpStream, profile: TerminalProfile) -> Self {
+        Self {
             stream,
-            parser: Parser::new(DisplayComponent::VGP5),
-        })
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
+        }
+    }
+
+    /// Makes reads return 0 when nothing was received before the timeout
+    /// instead of blocking until data arrives.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.stream.set_read_timeout(timeout).map_err(Into::into)
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.stream.peer_addr().map_err(Into::into)
     }
 
     #[inline(always)]
@@ -40,12 +72,21 @@ impl TcpTerminal {
 impl ReadableTerminal for TcpTerminal {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
         match self.stream.read(buf) {
-            Ok(bytes_read) => Ok(bytes_read),
+            //timeouts are reported as errors, nothing read means closed
+            Ok(0) if !buf.is_empty() => Err(Error::Disconnected),
+            Ok(bytes_read) => {
+                self.echo.feed(&mut self.parser, &buf[..bytes_read]);
+                Ok(bytes_read)
+            }
             Err(ref e) if e.kind() == ErrorKind::TimedOut => Ok(0),
             Err(ref e) if e.kind() == ErrorKind::WouldBlock => Ok(0),
             Err(e) => Err(Error::Io(e)),
         }
     }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
 }
 
 impl WriteableTerminal for TcpTerminal {
@@ -62,6 +103,22 @@ impl WriteableTerminal for TcpTerminal {
     fn flush(&mut self) -> Result<(), Error> {
         self.stream.flush().map_err(Into::into)
     }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
 }
 
 impl Contextualized for TcpTerminal {
@@ -0,0 +1,495 @@
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, ToTerminal, WriteableTerminal,
+};
+use crate::Error;
+use std::collections::VecDeque;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use teletel_protocol::parser::{Parser, Standard, TerminalProfile};
+
+pub(super) const IAC: u8 = 0xFF;
+const DONT: u8 = 0xFE;
+const DO: u8 = 0xFD;
+const WONT: u8 = 0xFC;
+const WILL: u8 = 0xFB;
+pub(super) const SB: u8 = 0xFA;
+pub(super) const SE: u8 = 0xF0;
+
+pub(super) const BINARY: u8 = 0x00;
+pub(super) const SUPPRESS_GO_AHEAD: u8 = 0x03;
+
+/// Options negotiated on both sides of the connection, the videotex stream
+/// is 8-bit clean with binary and there is no go ahead to strip.
+const OPTIONS: [u8; 2] = [BINARY, SUPPRESS_GO_AHEAD];
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum OptionState {
+    Disabled,
+    /// Enabling was asked and the answer is pending
+    Requested,
+    Enabled,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+enum State {
+    Data,
+    Iac,
+    Negotiation(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// Strips the telnet commands from the received stream and answers the
+/// option negotiations (RFC 854 and 1143)
+#[derive(Debug)]
+pub(super) struct Telnet {
+    state: State,
+    /// Options supported on our side
+    local_options: &'static [u8],
+    /// Options supported on the other side
+    remote_options: &'static [u8],
+    /// State of the options on our side, in the order of `local_options`
+    local: Vec<OptionState>,
+    remote: Vec<OptionState>,
+    /// Answers to the negotiations, to send back
+    pub(super) replies: Vec<u8>,
+    subnegotiation: Vec<u8>,
+    /// Subnegotiations received for the supported options, starting with
+    /// the option
+    pub(super) subnegotiations: Vec<Vec<u8>>,
+}
+
+impl Telnet {
+    pub(super) fn new(local_options: &'static [u8], remote_options: &'static [u8]) -> Self {
+        Self {
+            state: State::Data,
+            local_options,
+            remote_options,
+            local: vec![OptionState::Disabled; local_options.len()],
+            remote: vec![OptionState::Disabled; remote_options.len()],
+            replies: Vec::new(),
+            subnegotiation: Vec::new(),
+            subnegotiations: Vec::new(),
+        }
+    }
+
+    /// Asks to enable the supported options
+    pub(super) fn negotiate(&mut self) -> Vec<u8> {
+        let mut request = Vec::new();
+
+        for (state, option) in self.local.iter_mut().zip(self.local_options) {
+            *state = OptionState::Requested;
+            request.extend([IAC, WILL, *option]);
+        }
+
+        for (state, option) in self.remote.iter_mut().zip(self.remote_options) {
+            *state = OptionState::Requested;
+            request.extend([IAC, DO, *option]);
+        }
+
+        request
+    }
+
+    /// Decodes a received byte, returning it if it is part of the data
+    pub(super) fn decode(&mut self, byte: u8) -> Option<u8> {
+        let (state, data) = match (self.state, byte) {
+            (State::Data, IAC) => (State::Iac, None),
+            (State::Data, _) => (State::Data, Some(byte)),
+            (State::Iac, IAC) => (State::Data, Some(IAC)),
+            (State::Iac, WILL | WONT | DO | DONT) => (State::Negotiation(byte), None),
+            (State::Iac, SB) => {
+                self.subnegotiation.clear();
+                (State::Subnegotiation, None)
+            }
+            //go ahead, no operation and other commands without option
+            (State::Iac, _) => (State::Data, None),
+            (State::Negotiation(command), _) => {
+                self.answer(command, byte);
+                (State::Data, None)
+            }
+            (State::Subnegotiation, IAC) => (State::SubnegotiationIac, None),
+            (State::Subnegotiation, _) => {
+                self.subnegotiation.push(byte);
+                (State::Subnegotiation, None)
+            }
+            (State::SubnegotiationIac, SE) => {
+                let subnegotiation = std::mem::take(&mut self.subnegotiation);
+                if let Some(option) = subnegotiation.first() {
+                    if self.local_options.contains(option) || self.remote_options.contains(option) {
+                        self.subnegotiations.push(subnegotiation);
+                    }
+                }
+
+                (State::Data, None)
+            }
+            (State::SubnegotiationIac, _) => {
+                self.subnegotiation.push(byte);
+                (State::Subnegotiation, None)
+            }
+        };
+
+        self.state = state;
+        data
+    }
+
+    fn answer(&mut self, command: u8, option: u8) {
+        let (options, states, enable, accept, refuse) = match command {
+            DO => (self.local_options, &mut self.local, true, WILL, WONT),
+            DONT => (self.local_options, &mut self.local, false, WILL, WONT),
+            WILL => (self.remote_options, &mut self.remote, true, DO, DONT),
+            _ => (self.remote_options, &mut self.remote, false, DO, DONT),
+        };
+
+        let index = options.iter().position(|supported| *supported == option);
+
+        let Some(index) = index else {
+            //unsupported options are always disabled
+            if enable {
+                self.replies.extend([IAC, refuse, option]);
+            }
+
+            return;
+        };
+
+        let reply = match (states[index], enable) {
+            (OptionState::Disabled, true) => Some(accept),
+            (OptionState::Enabled, false) => Some(refuse),
+            _ => None,
+        };
+
+        states[index] = if enable {
+            OptionState::Enabled
+        } else {
+            OptionState::Disabled
+        };
+
+        if let Some(reply) = reply {
+            self.replies.extend([IAC, reply, option]);
+        }
+    }
+}
+
+/// A terminal behind a telnet connection, as exposed by most gateways and
+/// modem bridges. Binary mode and suppress go ahead are negotiated on
+/// connection, IAC bytes are escaped and telnet commands are stripped from
+/// the received data.
+pub struct TelnetTerminal {
+    stream: TcpStream,
+    parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
+    telnet: Telnet,
+}
+
+impl TelnetTerminal {
+    /// Connect to a specific address.
+    pub fn connect_to<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        Self::connect_with_profile(addr, TerminalProfile::default())
+    }
+
+    /// Connect to a specific address, the remote terminal being described
+    /// by the given profile.
+    pub fn connect_with_profile<A: ToSocketAddrs>(
+        addr: A,
+        profile: TerminalProfile,
+    ) -> Result<Self, Error> {
+        let stream = TcpStream::connect(addr).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("could not connect: {e}"),
+            ))
+        })?;
+
+        Self::from_stream(stream, profile)
+    }
+
+    /// Wraps an established connection, such as one accepted by a server,
+    /// and starts the negotiation.
+    pub fn from_stream(mut stream: TcpStream, profile: TerminalProfile) -> Result<Self, Error> {
+        let mut telnet = Telnet::new(&OPTIONS, &OPTIONS);
+        stream.write_all(&telnet.negotiate())?;
+
+        Ok(Self {
+            stream,
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
+            telnet,
+        })
+    }
+
+    /// Makes reads return 0 when nothing was received before the timeout
+    /// instead of blocking until data arrives.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.stream.set_read_timeout(timeout).map_err(Into::into)
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.stream.peer_addr().map_err(Into::into)
+    }
+
+    #[inline(always)]
+    pub fn send(&mut self, data: impl ToTerminal) -> Result<(), Error> {
+        data.to_terminal(self)
+    }
+}
+
+impl ReadableTerminal for TelnetTerminal {
+    /// Reads until data bytes arrive, chunks holding only telnet commands
+    /// are answered and do not count as data read
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        loop {
+            let bytes_read = match self.stream.read(buf) {
+                Ok(0) if !buf.is_empty() => return Err(Error::Disconnected),
+                Ok(bytes_read) => bytes_read,
+                Err(ref e) if e.kind() == ErrorKind::TimedOut => return Ok(0),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => return Ok(0),
+                Err(e) => return Err(Error::Io(e)),
+            };
+
+            let mut count = 0;
+            for i in 0..bytes_read {
+                if let Some(byte) = self.telnet.decode(buf[i]) {
+                    buf[count] = byte;
+                    count += 1;
+                }
+            }
+
+            if !self.telnet.replies.is_empty() {
+                self.stream.write_all(&self.telnet.replies)?;
+                self.telnet.replies.clear();
+            }
+
+            //binary and suppress go ahead have no parameters
+            self.telnet.subnegotiations.clear();
+
+            if count > 0 || bytes_read == 0 {
+                self.echo.feed(&mut self.parser, &buf[..count]);
+
+                return Ok(count);
+            }
+        }
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
+}
+
+impl WriteableTerminal for TelnetTerminal {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for byte in buf {
+            self.parser.consume(*byte)?;
+
+            if *byte == IAC {
+                self.stream.write_all(&[IAC, IAC])?;
+            } else {
+                self.stream.write_all(&[*byte])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn flush(&mut self) -> Result<(), Error> {
+        self.stream.flush().map_err(Into::into)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl Contextualized for TelnetTerminal {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    const ECHO: u8 = 0x01;
+
+    fn decode_all(telnet: &mut Telnet, bytes: &[u8]) -> Vec<u8> {
+        bytes
+            .iter()
+            .filter_map(|byte| telnet.decode(*byte))
+            .collect()
+    }
+
+    #[test]
+    fn test_commands_are_stripped() {
+        let mut telnet = Telnet::new(&OPTIONS, &OPTIONS);
+
+        let data = decode_all(
+            &mut telnet,
+            &[
+                b'a', IAC, IAC, IAC, 0xF9, b'b', IAC, SB, 0x18, 0x01, IAC, SE, b'c',
+            ],
+        );
+        assert_eq!(data, [b'a', IAC, b'b', b'c']);
+        assert!(telnet.replies.is_empty());
+        //the terminal type option is not supported
+        assert!(telnet.subnegotiations.is_empty());
+    }
+
+    #[test]
+    fn test_negotiation() {
+        let mut telnet = Telnet::new(&OPTIONS, &OPTIONS);
+        telnet.negotiate();
+
+        //answers to our requests are not answered again
+        decode_all(&mut telnet, &[IAC, DO, BINARY, IAC, WILL, BINARY]);
+        assert!(telnet.replies.is_empty());
+
+        decode_all(&mut telnet, &[IAC, DO, ECHO, IAC, WILL, ECHO]);
+        assert_eq!(telnet.replies, [IAC, WONT, ECHO, IAC, DONT, ECHO]);
+        telnet.replies.clear();
+
+        decode_all(
+            &mut telnet,
+            &[IAC, DONT, SUPPRESS_GO_AHEAD, IAC, WONT, BINARY],
+        );
+        assert_eq!(telnet.replies, [IAC, DONT, BINARY]);
+        telnet.replies.clear();
+        assert_eq!(telnet.local, [OptionState::Enabled, OptionState::Disabled]);
+        assert_eq!(
+            telnet.remote,
+            [OptionState::Disabled, OptionState::Requested]
+        );
+
+        decode_all(
+            &mut telnet,
+            &[IAC, DO, SUPPRESS_GO_AHEAD, IAC, DONT, BINARY],
+        );
+        assert_eq!(
+            telnet.replies,
+            [IAC, WILL, SUPPRESS_GO_AHEAD, IAC, WONT, BINARY]
+        );
+    }
+
+    #[test]
+    fn test_telnet_terminal() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut term = TelnetTerminal::connect_to(listener.local_addr().unwrap()).unwrap();
+        let (mut gateway, _) = listener.accept().unwrap();
+
+        let mut negotiation = [0; 12];
+        gateway.read_exact(&mut negotiation).unwrap();
+        assert_eq!(
+            &negotiation[..6],
+            [IAC, WILL, BINARY, IAC, WILL, SUPPRESS_GO_AHEAD]
+        );
+
+        gateway
+            .write_all(&[IAC, DO, ECHO, b'3', b'6', IAC, 0xF1, b'1', b'5'])
+            .unwrap();
+
+        let mut buf = [0; 16];
+        let mut data = Vec::<u8>::new();
+        while data.len() < 4 {
+            let count = term.read(&mut buf).unwrap();
+            data.extend(&buf[..count]);
+        }
+
+        assert_eq!(data, b"3615");
+
+        term.write(b"a").unwrap();
+        term.flush().unwrap();
+
+        let mut answer = [0; 4];
+        gateway.read_exact(&mut answer).unwrap();
+        assert_eq!(answer, [IAC, WONT, ECHO, b'a']);
+        //the keys typed are echoed locally before the written data
+        assert_eq!(term.ctx().grid.cell(1, 1).content, '3');
+        assert_eq!(term.ctx().grid.cell(5, 1).content, 'a');
+
+        drop(gateway);
+        assert!(matches!(term.read(&mut buf), Err(Error::Disconnected)));
+    }
+
+    #[test]
+    fn test_negotiation_before_response() {
+        use crate::protocol::ProtocolExtension;
+        use crate::terminal::BaudRate;
+        use teletel_protocol::codes::{B4800, ESC, PRO2, RESP_SPEED};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut term = TelnetTerminal::connect_to(listener.local_addr().unwrap()).unwrap();
+        term.set_read_timeout(Some(Duration::from_millis(100))).unwrap();
+        let (mut gateway, _) = listener.accept().unwrap();
+
+        let mut negotiation = [0; 12];
+        gateway.read_exact(&mut negotiation).unwrap();
+
+        let answer = thread::spawn(move || {
+            //speed request
+            let mut request = [0; 3];
+            gateway.read_exact(&mut request).unwrap();
+
+            gateway.write_all(&[IAC, DO, ECHO]).unwrap();
+            gateway.flush().unwrap();
+            thread::sleep(Duration::from_millis(20));
+            gateway.write_all(&[ESC, PRO2, RESP_SPEED, B4800]).unwrap();
+
+            let mut reply = [0; 3];
+            gateway.read_exact(&mut reply).unwrap();
+            reply
+        });
+
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B4800);
+        assert_eq!(answer.join().unwrap(), [IAC, WONT, ECHO]);
+    }
+
+    #[test]
+    fn test_keys_typed_during_request() {
+        use crate::protocol::ProtocolExtension;
+        use crate::terminal::{BaudRate, KeyEvent};
+        use teletel_protocol::codes::{B4800, ESC, PRO2, RESP_SPEED};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut term = TelnetTerminal::connect_to(listener.local_addr().unwrap()).unwrap();
+        term.set_read_timeout(Some(Duration::from_millis(100))).unwrap();
+        let (mut gateway, _) = listener.accept().unwrap();
+
+        let mut negotiation = [0; 12];
+        gateway.read_exact(&mut negotiation).unwrap();
+        gateway.write_all(b"a").unwrap();
+
+        let answer = thread::spawn(move || {
+            //speed request
+            let mut request = [0; 3];
+            gateway.read_exact(&mut request).unwrap();
+
+            gateway.write_all(&[b'b', ESC, PRO2, RESP_SPEED, B4800]).unwrap();
+            gateway.flush().unwrap();
+            gateway
+        });
+
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B4800);
+        let _gateway = answer.join().unwrap();
+
+        let timeout = Duration::from_millis(10);
+        assert_eq!(term.read_key(timeout).unwrap(), Some(KeyEvent::Char('a')));
+        assert_eq!(term.read_key(timeout).unwrap(), Some(KeyEvent::Char('b')));
+        assert_eq!(term.read_key(timeout).unwrap(), None);
+    }
+}
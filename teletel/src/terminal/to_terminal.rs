@@ -1,5 +1,7 @@
 use crate::terminal::WriteableTerminal;
 use crate::Error;
+use alloc::string::String;
+use alloc::vec::Vec;
 use teletel_protocol::codes::*;
 
 pub trait ToTerminal {
@@ -53,7 +55,13 @@ impl ToTerminal for char {
                     return Err(Error::InvalidCharacter(*self));
                 }
 
+                #[cfg(feature = "std")]
                 let decoded = unidecode::unidecode_char(c).as_bytes();
+                //only the ascii characters are sent without std
+                #[cfg(not(feature = "std"))]
+                let mut utf8 = [0; 4];
+                #[cfg(not(feature = "std"))]
+                let decoded = c.encode_utf8(&mut utf8).as_bytes();
                 if decoded.len() > 1 || decoded[0] < 0x20 || decoded[0] > 0x7F {
                     return Err(Error::InvalidCharacter(*self));
                 }
@@ -102,7 +110,9 @@ impl<F: Fn(&mut dyn WriteableTerminal) -> Result<(), Error>> ToTerminal for F {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::terminal::{Context, Contextualized, RawBuffer, ReadableTerminal};
+    use crate::terminal::{
+        Context, Contextualized, FunctionKey, KeyEvent, RawBuffer, ReadableTerminal,
+    };
     use std::cmp;
     use std::time::Duration;
     use teletel_protocol::parser::DisplayComponent;
@@ -328,4 +338,18 @@ mod tests {
         let data = buffer.read_until_enter().unwrap();
         assert_eq!(data, [0x01, 0x02, 0x03]);
     }
+
+    #[test]
+    fn test_read_key() {
+        let mut buffer = MockReceiver::from(vec![SS2, ACUTE, b'e', SEP, 0x41]);
+
+        let key = buffer.read_key(Duration::from_millis(10)).unwrap();
+        assert_eq!(key, Some(KeyEvent::Char('é')));
+
+        let key = buffer.read_key(Duration::from_millis(10)).unwrap();
+        assert_eq!(key, Some(KeyEvent::Function(FunctionKey::Envoi)));
+
+        let key = buffer.read_key(Duration::from_millis(10)).unwrap();
+        assert_eq!(key, None);
+    }
 }
@@ -0,0 +1,397 @@
+use crate::error::Error;
+use crate::protocol::{ProtocolExtension, SpeedAwareTerminal};
+use crate::terminal::line::{self, SerialConfig};
+use crate::terminal::{
+    BaudRate, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, Response, ToTerminal,
+    WriteableTerminal,
+};
+use alloc::collections::VecDeque;
+use core::time::Duration;
+use embedded_hal::delay::DelayNs;
+use embedded_io::{Read, ReadReady, Write};
+use teletel_protocol::codes::FF;
+use teletel_protocol::parser::{Context, Parser, Standard, TerminalProfile};
+
+/// Callback switching the speed of the UART
+pub type SetBaudRate<U> = fn(&mut U, BaudRate) -> Result<(), Error>;
+
+/// A terminal wired to the UART of a microcontroller, such as an ESP32 or
+/// an Arduino, through any `embedded-io` implementation. The UART must be
+/// configured in 7E1 at the speed of the terminal, 1200 bauds when it is
+/// turned on.
+///
+/// Reads wait for data with the given delay, as long as the read timeout of
+/// the [`SerialConfig`], before returning 0. The delay is also used by
+/// [`ReadableTerminal::wait`] and counts the time waited, which serves as
+/// the clock of [`ReadableTerminal::read_key`] without std. The speed of the UART can only be
+/// changed by [`SpeedAwareTerminal`] once a callback reconfiguring it was
+/// given with [`UartTerminal::on_speed_change`].
+pub struct UartTerminal<U, D, F = SetBaudRate<U>> {
+    uart: U,
+    delay: D,
+    baud_rate: BaudRate,
+    set_baud_rate: Option<F>,
+    config: SerialConfig,
+    /// Time spent in the reads since the last call to `wait`
+    waited: Duration,
+    parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
+}
+
+impl<U, D> UartTerminal<U, D> {
+    pub fn new(uart: U, delay: D, baud_rate: BaudRate) -> Self {
+        Self {
+            uart,
+            delay,
+            baud_rate,
+            set_baud_rate: None,
+            config: SerialConfig::default(),
+            waited: Duration::ZERO,
+            parser: Parser::from_profile(TerminalProfile::default()),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
+        }
+    }
+}
+
+impl<U, D, F> UartTerminal<U, D, F> {
+    /// Describes the terminal plugged in, a Minitel 2 by default
+    pub fn with_profile(mut self, profile: TerminalProfile) -> Self {
+        self.parser.set_profile(profile);
+        self
+    }
+
+    /// Changes the read timeouts, the switch delay and how the speed of the
+    /// terminal is looked for, the framing and the RTS and DTR lines being
+    /// left to the configuration of the UART
+    pub fn with_config(mut self, config: SerialConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Calls `set_baud_rate` to reconfigure the UART once the terminal
+    /// was asked to switch to another speed
+    pub fn on_speed_change<G: FnMut(&mut U, BaudRate) -> Result<(), Error>>(
+        self,
+        set_baud_rate: G,
+    ) -> UartTerminal<U, D, G> {
+        UartTerminal {
+            uart: self.uart,
+            delay: self.delay,
+            baud_rate: self.baud_rate,
+            set_baud_rate: Some(set_baud_rate),
+            config: self.config,
+            waited: self.waited,
+            parser: self.parser,
+            echo: self.echo,
+            keys: self.keys,
+        }
+    }
+
+    /// Speed the UART is configured at
+    pub fn baud_rate(&self) -> BaudRate {
+        self.baud_rate
+    }
+
+    /// Gives the UART and the delay back
+    pub fn release(self) -> (U, D) {
+        (self.uart, self.delay)
+    }
+}
+
+impl<U, D, F: FnMut(&mut U, BaudRate) -> Result<(), Error>> UartTerminal<U, D, F> {
+    fn switch(&mut self, baud_rate: BaudRate) -> Result<(), Error> {
+        match self.set_baud_rate.as_mut() {
+            Some(set_baud_rate) => set_baud_rate(&mut self.uart, baud_rate)?,
+            None => return Err(Error::UnsupportedSpeed(baud_rate)),
+        }
+
+        self.baud_rate = baud_rate;
+
+        Ok(())
+    }
+}
+
+impl<U: Read + ReadReady, D: DelayNs, F> ReadableTerminal for UartTerminal<U, D, F> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        let timeout = self.config.read_timeout(self.baud_rate).as_millis();
+
+        let mut waited = 0;
+        while !self.uart.read_ready().map_err(uart_error)? {
+            if waited == timeout {
+                return Ok(0);
+            }
+
+            self.delay.delay_ms(1);
+            self.waited += Duration::from_millis(1);
+            waited += 1;
+        }
+
+        let bytes_read = self.uart.read(buf).map_err(uart_error)?;
+        self.echo.feed(&mut self.parser, &buf[..bytes_read]);
+
+        Ok(bytes_read)
+    }
+
+    fn wait(&mut self, duration: Duration) -> Duration {
+        self.delay.delay_us(duration.as_micros() as u32);
+
+        duration + core::mem::take(&mut self.waited)
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
+}
+
+impl<U: Write, D, F> WriteableTerminal for UartTerminal<U, D, F> {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for byte in buf {
+            self.parser.consume(*byte)?;
+        }
+
+        self.uart.write_all(buf).map_err(uart_error)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.uart.flush().map_err(uart_error)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl<U, D, F> Contextualized for UartTerminal<U, D, F> {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
+
+impl<U, D, F> SpeedAwareTerminal for UartTerminal<U, D, F>
+where
+    U: Read + ReadReady + Write,
+    D: DelayNs,
+    F: FnMut(&mut U, BaudRate) -> Result<(), Error>,
+{
+    /// Probes the speeds in the order of the configuration until the
+    /// terminal answers, fails with [`Error::ConnectionFailure`] once every
+    /// attempt was made. Without callback, only checks that the terminal
+    /// uses the speed of the UART.
+    fn match_connector_speed(&mut self) -> Result<(), Error> {
+        if self.set_baud_rate.is_none() {
+            return match self.get_connector_speed()? {
+                speed if speed == self.baud_rate => Ok(()),
+                speed => Err(Error::UnexpectedResponse(Response::Speed(speed))),
+            };
+        }
+
+        let config = self.config.clone();
+
+        line::match_connector_speed(self, &config, |term, baud_rate| {
+            if term.baud_rate != baud_rate {
+                term.switch(baud_rate)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn set_connector_speed(&mut self, baud_rate: BaudRate) -> Result<(), Error> {
+        if !self.parser.profile().supports_speed(baud_rate as u16) || self.set_baud_rate.is_none() {
+            return Err(Error::UnsupportedSpeed(baud_rate));
+        }
+
+        self.read_to_vec()?;
+        baud_rate.to_terminal(self)?;
+        self.flush()?;
+
+        let delay = self.config.switch_delay;
+        self.delay.delay_us(delay.as_micros() as u32);
+        self.switch(baud_rate)?;
+
+        self.reset()?;
+        FF.to_terminal(self)?;
+        self.flush()?;
+
+        Ok(())
+    }
+}
+
+fn uart_error<E: embedded_io::Error>(error: E) -> Error {
+    Error::Uart(error.kind())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::mock::MockMinitel;
+    use crate::terminal::KeyEvent;
+    use std::collections::VecDeque;
+    use std::convert::Infallible;
+    use teletel_protocol::codes::{B1200, B4800};
+
+    /// UART wired to a Minitel
+    struct MockUart {
+        baud_rate: BaudRate,
+        minitel: MockMinitel,
+        sent: Vec<u8>,
+        input: VecDeque<u8>,
+    }
+
+    impl MockUart {
+        fn new(baud_rate: BaudRate) -> Self {
+            Self {
+                baud_rate,
+                minitel: MockMinitel::new(B1200),
+                sent: Vec::new(),
+                input: VecDeque::new(),
+            }
+        }
+    }
+
+    impl embedded_io::ErrorType for MockUart {
+        type Error = Infallible;
+    }
+
+    impl Read for MockUart {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+            let count = buf.len().min(self.input.len());
+            for (dst, src) in buf.iter_mut().zip(self.input.drain(..count)) {
+                *dst = src;
+            }
+
+            Ok(count)
+        }
+    }
+
+    impl ReadReady for MockUart {
+        fn read_ready(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.input.is_empty())
+        }
+    }
+
+    impl Write for MockUart {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Infallible> {
+            self.sent.extend(buf);
+
+            for byte in buf {
+                let answer = self.minitel.receive(*byte, self.baud_rate as u32);
+                self.input.extend(answer);
+            }
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    /// Delay keeping track of the time waited instead of waiting
+    #[derive(Default)]
+    struct MockDelay {
+        elapsed: Duration,
+    }
+
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, ns: u32) {
+            self.elapsed += Duration::from_nanos(ns as u64);
+        }
+    }
+
+    #[test]
+    fn test_uart_terminal() {
+        let mut term = UartTerminal::new(
+            MockUart::new(BaudRate::B1200),
+            MockDelay::default(),
+            BaudRate::B1200,
+        );
+
+        term.write(b"Bonjour").unwrap();
+        assert_eq!(term.uart.sent, b"Bonjour");
+        assert_eq!(term.ctx().grid.cell(1, 1).content, 'B');
+
+        term.uart.input.extend(b"a");
+        assert_eq!(
+            term.read_key(Duration::from_secs(1)).unwrap(),
+            Some(KeyEvent::Char('a'))
+        );
+
+        let mut buf = [0; 4];
+        assert_eq!(term.read(&mut buf).unwrap(), 0);
+        assert_eq!(term.delay.elapsed, Duration::from_millis(180));
+
+        //the time waited in the read is counted by the next wait only
+        assert_eq!(term.wait(Duration::from_millis(1)), Duration::from_millis(181));
+        assert_eq!(term.wait(Duration::from_millis(1)), Duration::from_millis(1));
+        assert_eq!(term.delay.elapsed, Duration::from_millis(182));
+
+        assert!(term.match_connector_speed().is_ok());
+        assert!(matches!(
+            term.set_connector_speed(BaudRate::B4800),
+            Err(Error::UnsupportedSpeed(BaudRate::B4800))
+        ));
+    }
+
+    #[test]
+    fn test_match_connector_speed() {
+        let mut uart = MockUart::new(BaudRate::B1200);
+        uart.minitel.speed = B4800;
+
+        let mut switches = Vec::new();
+        let mut term = UartTerminal::new(uart, MockDelay::default(), BaudRate::B1200)
+            .with_profile(TerminalProfile::MINITEL_1B)
+            .on_speed_change(|uart: &mut MockUart, baud_rate| {
+                switches.push(baud_rate);
+                uart.baud_rate = baud_rate;
+                Ok(())
+            });
+
+        term.match_connector_speed().unwrap();
+        assert_eq!(term.baud_rate(), BaudRate::B4800);
+        assert_eq!(term.get_connector_speed().unwrap(), BaudRate::B4800);
+
+        drop(term);
+        assert_eq!(switches, [BaudRate::B4800, BaudRate::B4800]);
+    }
+
+    #[test]
+    fn test_connection_failure() {
+        let mut uart = MockUart::new(BaudRate::B1200);
+        uart.minitel.speed = B4800;
+
+        let mut term = UartTerminal::new(uart, MockDelay::default(), BaudRate::B1200)
+            .with_config(
+                SerialConfig::default()
+                    .with_read_timeout(BaudRate::B300, Duration::from_millis(10))
+                    .with_probing_order([BaudRate::B1200, BaudRate::B300])
+                    .with_max_attempts(2),
+            )
+            .on_speed_change(|uart: &mut MockUart, baud_rate| {
+                uart.baud_rate = baud_rate;
+                Ok(())
+            });
+
+        assert!(matches!(
+            term.match_connector_speed(),
+            Err(Error::ConnectionFailure)
+        ));
+        //every attempt discards the input then waits for the answer once
+        //at each speed
+        assert_eq!(term.delay.elapsed, Duration::from_millis(2 * 2 * (180 + 10)));
+    }
+}
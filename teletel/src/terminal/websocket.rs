@@ -0,0 +1,327 @@
+use crate::terminal::{
+    Context, Contextualized, KeyEvent, LocalEcho, ReadableTerminal, ToTerminal, WriteableTerminal,
+};
+use crate::Error;
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use teletel_protocol::parser::{Parser, Standard, TerminalProfile};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::error::ProtocolError;
+use tungstenite::handshake::{HandshakeError, HandshakeRole};
+use tungstenite::{Message, WebSocket};
+
+/// A terminal reached over WebSocket, such as a browser emulator or a
+/// gateway. Binary and text frames are both accepted, text frames holding
+/// one character per byte. The characters of the received text frames above
+/// U+00FF do not fit in a byte and are skipped.
+pub struct WebSocketTerminal {
+    socket: WebSocket<TcpStream>,
+    parser: Parser,
+    echo: LocalEcho,
+    keys: VecDeque<KeyEvent>,
+    /// Data received but not read yet, a frame can be larger than the
+    /// buffer given to read
+    input: VecDeque<u8>,
+    /// Data written since the last flush, sent in a single frame
+    output: Vec<u8>,
+    text: bool,
+}
+
+impl WebSocketTerminal {
+    /// Connect to a `ws://` url.
+    pub fn connect_to(url: &str) -> Result<Self, Error> {
+        Self::connect_with_profile(url, TerminalProfile::default())
+    }
+
+    /// Connect to a `ws://` url, the remote terminal being described by
+    /// the given profile.
+    pub fn connect_with_profile(url: &str, profile: TerminalProfile) -> Result<Self, Error> {
+        let request = url.into_client_request().map_err(into_error)?;
+
+        let uri = request.uri();
+        if uri.scheme_str() != Some("ws") {
+            return Err(Error::Io(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!("unsupported url {url}, only ws:// is supported"),
+            )));
+        }
+
+        let host = uri.host().unwrap_or_default().to_string();
+        let port = uri.port_u16().unwrap_or(80);
+        let stream = TcpStream::connect((host, port)).map_err(|e| {
+            Error::Io(std::io::Error::new(
+                e.kind(),
+                format!("could not connect: {e}"),
+            ))
+        })?;
+
+        let (socket, _) = tungstenite::client(request, stream).map_err(handshake_error)?;
+
+        Ok(Self::from_socket(socket, profile))
+    }
+
+    /// Wraps an established WebSocket, such as one accepted by a
+    /// [`WebSocketListener`].
+    pub fn from_socket(socket: WebSocket<TcpStream>, profile: TerminalProfile) -> Self {
+        Self {
+            socket,
+            parser: Parser::from_profile(profile),
+            echo: LocalEcho::new(),
+            keys: VecDeque::new(),
+            input: VecDeque::new(),
+            output: Vec::new(),
+            text: false,
+        }
+    }
+
+    /// Sends the data in text frames instead of binary ones, for the
+    /// clients only handling text. Each byte is sent as the Latin-1
+    /// character of the same code, the bytes above 0x7F are thus encoded
+    /// on two bytes in the UTF-8 frame.
+    pub fn set_text_frames(&mut self, text: bool) {
+        self.text = text;
+    }
+
+    /// Makes reads return 0 when nothing was received before the timeout
+    /// instead of blocking until data arrives.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.socket
+            .get_ref()
+            .set_read_timeout(timeout)
+            .map_err(Into::into)
+    }
+
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.socket.get_ref().peer_addr().map_err(Into::into)
+    }
+
+    #[inline(always)]
+    pub fn send(&mut self, data: impl ToTerminal) -> Result<(), Error> {
+        data.to_terminal(self)
+    }
+}
+
+impl ReadableTerminal for WebSocketTerminal {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        while self.input.is_empty() && !buf.is_empty() {
+            match self.socket.read() {
+                Ok(Message::Binary(data)) => self.input.extend(data),
+                Ok(Message::Text(text)) => self
+                    .input
+                    .extend(text.chars().filter_map(|c| u8::try_from(c as u32).ok())),
+                //pings are answered by the next read or flush
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(ref e)) if e.kind() == ErrorKind::TimedOut => {
+                    return Ok(0)
+                }
+                Err(tungstenite::Error::Io(ref e)) if e.kind() == ErrorKind::WouldBlock => {
+                    return Ok(0)
+                }
+                Err(e) => return Err(into_error(e)),
+            }
+        }
+
+        let count = buf.len().min(self.input.len());
+        for (dst, src) in buf.iter_mut().zip(self.input.drain(..count)) {
+            *dst = src;
+        }
+
+        self.echo.feed(&mut self.parser, &buf[..count]);
+
+        Ok(count)
+    }
+
+    fn pending_keys(&mut self) -> Option<&mut VecDeque<KeyEvent>> {
+        Some(&mut self.keys)
+    }
+}
+
+impl WriteableTerminal for WebSocketTerminal {
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        for byte in buf {
+            self.parser.consume(*byte)?;
+            self.output.push(*byte);
+        }
+
+        Ok(())
+    }
+
+    /// Sends the data written since the last flush in a single frame
+    fn flush(&mut self) -> Result<(), Error> {
+        if !self.output.is_empty() {
+            let data = std::mem::take(&mut self.output);
+            let message = if self.text {
+                //one Latin-1 character per byte
+                Message::Text(data.iter().map(|byte| *byte as char).collect())
+            } else {
+                Message::Binary(data)
+            };
+
+            self.socket.write(message).map_err(into_error)?;
+        }
+
+        self.socket.flush().map_err(into_error)
+    }
+
+    fn profile(&self) -> Option<TerminalProfile> {
+        Some(*self.parser.profile())
+    }
+
+    fn standard(&self) -> Option<Standard> {
+        Some(self.parser.ctx().standard)
+    }
+
+    fn set_profile(&mut self, profile: TerminalProfile) {
+        self.parser.set_profile(profile);
+    }
+
+    fn observe(&mut self, response: &[u8]) {
+        self.parser.observe(response);
+    }
+}
+
+impl Contextualized for WebSocketTerminal {
+    fn ctx(&self) -> &Context {
+        self.parser.ctx()
+    }
+}
+
+/// Accepts the terminals connecting over WebSocket, each connection
+/// getting its own terminal with its own parser and context.
+pub struct WebSocketListener {
+    listener: TcpListener,
+    profile: TerminalProfile,
+}
+
+impl WebSocketListener {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            profile: TerminalProfile::default(),
+        })
+    }
+
+    /// Describes the terminals expected to connect, a Minitel 2 by default
+    pub fn with_profile(mut self, profile: TerminalProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        self.listener.local_addr().map_err(Into::into)
+    }
+
+    /// Waits for the next terminal to connect and completes the handshake
+    pub fn accept(&self) -> Result<(WebSocketTerminal, SocketAddr), Error> {
+        let (stream, peer) = self.listener.accept()?;
+        stream.set_nodelay(true)?;
+
+        let socket = tungstenite::accept(stream).map_err(handshake_error)?;
+
+        Ok((WebSocketTerminal::from_socket(socket, self.profile), peer))
+    }
+}
+
+fn into_error(error: tungstenite::Error) -> Error {
+    match error {
+        tungstenite::Error::ConnectionClosed
+        | tungstenite::Error::AlreadyClosed
+        | tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake) => {
+            Error::Disconnected
+        }
+        tungstenite::Error::Io(e) => Error::Io(e),
+        e => Error::Io(std::io::Error::new(ErrorKind::InvalidData, e.to_string())),
+    }
+}
+
+fn handshake_error<R: HandshakeRole>(error: HandshakeError<R>) -> Error {
+    match error {
+        HandshakeError::Failure(e) => into_error(e),
+        //only happens on non-blocking streams
+        HandshakeError::Interrupted(_) => Error::Io(ErrorKind::WouldBlock.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// Server sending back every data frame it receives
+    fn echo_server() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut socket = tungstenite::accept(stream).unwrap();
+
+            while let Ok(message) = socket.read() {
+                if message.is_binary() || message.is_text() {
+                    socket.send(message).unwrap();
+                }
+            }
+        });
+
+        addr
+    }
+
+    #[test]
+    fn test_websocket_terminal() {
+        let addr = echo_server();
+        let mut term = WebSocketTerminal::connect_to(&format!("ws://{addr}")).unwrap();
+
+        term.write(b"36").unwrap();
+        term.write(b"15").unwrap();
+        term.flush().unwrap();
+
+        let mut buf = [0; 2];
+        term.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"36");
+        term.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"15");
+
+        //the keys echoed locally follow the written data
+        assert_eq!(term.ctx().grid.cell(1, 1).content, '3');
+        assert_eq!(term.ctx().grid.cell(5, 1).content, '3');
+
+        term.set_text_frames(true);
+        term.write(b"ulla").unwrap();
+        term.flush().unwrap();
+
+        let mut buf = [0; 4];
+        term.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ulla");
+    }
+
+    #[test]
+    fn test_websocket_listener() {
+        let listener = WebSocketListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let url = format!("ws://{addr}");
+            let (mut socket, _) =
+                tungstenite::client(url, TcpStream::connect(addr).unwrap()).unwrap();
+
+            //characters that do not fit in a byte are skipped
+            socket.send(Message::Text("36€15".into())).unwrap();
+            socket.read().unwrap()
+        });
+
+        let (mut term, _) = listener.accept().unwrap();
+
+        let mut buf = [0; 4];
+        term.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"3615");
+
+        term.send("Bonjour").unwrap();
+        term.flush().unwrap();
+        assert_eq!(client.join().unwrap(), Message::Binary(b"Bonjour".to_vec()));
+
+        //the client dropped the connection without closing it
+        assert!(matches!(term.read(&mut buf), Err(Error::Disconnected)));
+    }
+}
@@ -2,8 +2,12 @@
 
 use crate::terminal::{ToTerminal, WriteableTerminal};
 use crate::{declare, Error};
+use alloc::vec;
+#[cfg(feature = "std")]
 use std::io::Result as IoResult;
+use teletel_protocol::codes::drcs::*;
 use teletel_protocol::codes::*;
+use teletel_protocol::parser::{CharacterSet, Glyph};
 
 declare!(Clear, [FF]);
 declare!(ClearRow, [CSI_2_K]);
@@ -130,7 +134,7 @@ pub struct SetCursor(pub u8, pub u8);
 impl ToTerminal for SetCursor {
     fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
         assert!(self.0 > 0);
-        assert!(self.0 <= 40);
+        assert!(self.0 <= 80); //80 columns in mixte mode, p144
         assert!(self.1 > 0); //p96
         assert!(self.1 <= 24);
 
@@ -193,6 +197,7 @@ impl<B: AsRef<[u8]> + ToTerminal> Videotex<B> {
     }
 }
 
+#[cfg(feature = "std")]
 impl Videotex<Vec<u8>> {
     pub fn from_path(path: &str) -> IoResult<Self> {
         Ok(Self {
@@ -222,6 +227,70 @@ impl ToTerminal for ScreenMasking {
     }
 }
 
+/// Redefines the characters of the given set starting at the given code with
+/// custom 8x10 glyphs. Each glyph is made of 10 rows of 8 pixels, the leftmost
+/// pixel being the most significant bit. Characters of the G0 set can be
+/// redefined from 0x21 to 0x7F. The glyphs are only displayed in place of the
+/// standard characters inside a `CustomGlyphs` sequence.
+pub struct DefineGlyphs<G: AsRef<[Glyph]>>(pub CharacterSet, pub u8, pub G);
+
+impl<G: AsRef<[Glyph]>> ToTerminal for DefineGlyphs<G> {
+    fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
+        let glyphs = self.2.as_ref();
+        assert!(self.1 > 0x20);
+        assert!(self.1 as usize + glyphs.len() <= 0x80);
+
+        let set = match self.0 {
+            CharacterSet::G0 => DRCS_G0,
+            CharacterSet::G1 => DRCS_G1,
+        };
+
+        [US, DOWNLOAD, 0x20, 0x20, 0x20, set, DOWNLOAD_FORMAT].to_terminal(term)?;
+        [US, DOWNLOAD, self.1, GLYPH_SEPARATOR].to_terminal(term)?;
+
+        for glyph in glyphs {
+            let mut data = [0x40; 14];
+
+            //pixels are sent 6 by 6 in bytes between 0x40 and 0x7F
+            for pixel in 0..80 {
+                if glyph[pixel / 8] & (0x80 >> (pixel % 8)) != 0 {
+                    data[pixel / 6] |= 0x20 >> (pixel % 6);
+                }
+            }
+
+            //blank bytes at the end of a glyph can be omitted
+            let length = data
+                .iter()
+                .rposition(|byte| *byte != 0x40)
+                .map_or(0, |i| i + 1);
+            data[..length].to_vec().to_terminal(term)?;
+            GLYPH_SEPARATOR.to_terminal(term)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Displays the characters of the given set with the glyphs downloaded through
+/// `DefineGlyphs` instead of the standard ones.
+pub struct CustomGlyphs<T: ToTerminal>(pub CharacterSet, pub T);
+
+impl<T: ToTerminal> ToTerminal for CustomGlyphs<T> {
+    fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
+        match self.0 {
+            CharacterSet::G0 => [ESC, DESIGNATE_G0, 0x20, DRCS_G0].to_terminal(term)?,
+            CharacterSet::G1 => [ESC, DESIGNATE_G1, 0x20, DRCS_G1].to_terminal(term)?,
+        }
+
+        self.1.to_terminal(term)?;
+
+        match self.0 {
+            CharacterSet::G0 => [ESC, DESIGNATE_G0, STANDARD_G0].to_terminal(term),
+            CharacterSet::G1 => [ESC, DESIGNATE_G1, STANDARD_G1].to_terminal(term),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -446,7 +515,7 @@ mod tests {
 
         assert_panics!(|| {
             let mut data = RawBuffer::new();
-            SetCursor(41, 10).to_terminal(&mut data).unwrap();
+            SetCursor(81, 10).to_terminal(&mut data).unwrap();
         });
 
         assert_panics!(|| {
@@ -570,4 +639,60 @@ mod tests {
         ScreenMasking::Off.to_terminal(&mut data).unwrap();
         assert_eq!(data.data(), [0x1B, 0x23, 0x20, 0x5F]);
     }
+
+    #[test]
+    fn test_define_glyphs() {
+        let arrow = [
+            0b00011000, 0b00111100, 0b01111110, 0b11111111, 0b00011000, 0b00011000, 0b00011000,
+            0b00011000, 0b00011000, 0b00000000,
+        ];
+
+        let mut data = RawBuffer::new();
+        DefineGlyphs(CharacterSet::G0, 0x41, [arrow, [0; 10]])
+            .to_terminal(&mut data)
+            .unwrap();
+
+        assert_eq!(
+            data.data(),
+            [
+                0x1F, 0x23, 0x20, 0x20, 0x20, 0x42, 0x49, 0x1F, 0x23, 0x41, 0x30, 0x46, 0x43, 0x71,
+                0x7E, 0x7F, 0x71, 0x60, 0x58, 0x46, 0x41, 0x60, 0x58, 0x30, 0x30,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_define_glyphs_stored_in_context() {
+        use crate::terminal::{Buffer, Contextualized};
+
+        let glyph = [0xFF, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0x81, 0xFF];
+
+        let mut buffer = Buffer::new();
+        buffer
+            .send(DefineGlyphs(CharacterSet::G1, 0x21, [glyph]))
+            .unwrap();
+        buffer
+            .send(CustomGlyphs(CharacterSet::G1, SemiGraphic(0x21)))
+            .unwrap();
+
+        assert_eq!(
+            buffer.ctx().drcs.glyph(CharacterSet::G1, 0x21),
+            Some(&glyph)
+        );
+        assert!(buffer.ctx().grid.cell(1, 1).attributes.drcs);
+        assert!(!buffer.ctx().drcs.is_designated(CharacterSet::G1));
+    }
+
+    #[test]
+    fn test_custom_glyphs() {
+        let mut data = RawBuffer::new();
+        CustomGlyphs(CharacterSet::G0, 'A')
+            .to_terminal(&mut data)
+            .unwrap();
+
+        assert_eq!(
+            data.data(),
+            [0x1B, 0x28, 0x20, 0x42, b'A', 0x1B, 0x28, 0x40]
+        );
+    }
 }
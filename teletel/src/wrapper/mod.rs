@@ -12,9 +12,9 @@ macro_rules! declare {
 
         impl $(<$($lt$(:$clt$(+$dlt)*)?),+>)? $crate::terminal::ToTerminal for $name $(<$($lt),+>)? {
             #[inline(always)]
-            fn to_terminal(&$self, term: &mut dyn $crate::terminal::WriteableTerminal) -> std::result::Result<(), $crate::Error> {
+            fn to_terminal(&$self, term: &mut dyn $crate::terminal::WriteableTerminal) -> core::result::Result<(), $crate::Error> {
                 $($crate::terminal::ToTerminal::to_terminal(&$code, term)?;)+
-                std::result::Result::Ok(())
+                core::result::Result::Ok(())
             }
         }
     };
@@ -1,29 +1,25 @@
-use std::thread;
-use std::time::Duration;
-use crate::terminal::{BaudRate, ReadableTerminal, WriteableTerminal};
+use alloc::vec::Vec;
+use core::time::Duration;
+use crate::terminal::{
+    BaudRate, Contextualized, Feature, Identification, Module, ReadableTerminal, Response, Status,
+    TerminalProfile, ToTerminal, WriteableTerminal,
+};
 use crate::Error;
+use teletel_protocol::codes::teleinformatique::*;
 use teletel_protocol::codes::*;
+use teletel_protocol::parser::PageMode as ParserPageMode;
+use teletel_protocol::parser::Standard as ParserStandard;
 
-macro_rules! count_args {
-    ($item:pat) => { 1 };
-    ($item:pat, $($rest:pat),*) => {
-        1 + count_args!($($rest),*)
-    }
-}
-
-macro_rules! expect_sequence {
-    ($self:ident, [$($item:pat),+ $(,)?]) => {
-        expect_sequence!($self, [$($item),+] => Ok(()))
+macro_rules! expect_response {
+    ($self:ident, $response:pat $(if $guard:expr)?) => {
+        expect_response!($self, $response $(if $guard)? => Ok(()))
     };
-    ($self:ident, [$($item:pat),+ $(,)?] => $then:expr) => {{
-        let mut buffer = vec![0; count_args!($($item),+)];
-        $self.read_exact(&mut buffer)?;
-
-        match buffer.as_slice() {
-            [$($item),+] => $then,
-            _ => Err(Error::UnexpectedSequence(buffer))
+    ($self:ident, $response:pat $(if $guard:expr)? => $then:expr) => {
+        match $self.read_response()? {
+            $response $(if $guard)? => $then,
+            response => Err(Error::UnexpectedResponse(response)),
         }
-    }};
+    };
 }
 
 pub enum PageMode {
@@ -31,6 +27,110 @@ pub enum PageMode {
     Scroll,
 }
 
+/// Switches between the 40 columns videotex mode and the 80 columns mixte
+/// mode. Only the G0 character set is available in mixte mode, semi-graphic,
+/// special characters and double sizes are refused by the terminal.
+///
+/// Documented on page 144.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Mode {
+    Mixte,
+    Videotex,
+}
+
+impl ToTerminal for Mode {
+    fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
+        match self {
+            Mode::Mixte => [ESC, PRO2, MIXED, TO_MIXED].to_terminal(term),
+            Mode::Videotex => [ESC, PRO2, MIXED, TO_VIDEOTEX].to_terminal(term),
+        }
+    }
+}
+
+/// Switches between the teletel standard (videotex and mixte modes) and
+/// the téléinformatique standard, an ASCII mode driven by ANSI escape
+/// sequences close to the VT100.
+///
+/// Documented on page 144.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Standard {
+    Teleinformatique,
+    Teletel,
+}
+
+impl Standard {
+    /// Sequence switching the terminal to this standard
+    fn sequence(&self) -> &'static [u8] {
+        match self {
+            Standard::Teleinformatique => &[ESC, PRO2, TELEINFORMATIQUE, TO_TELEINFORMATIQUE],
+            Standard::Teletel => &TO_TELETEL,
+        }
+    }
+}
+
+impl ToTerminal for Standard {
+    fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
+        term.write(self.sequence())
+    }
+}
+
+/// Sets the number of columns of the screen while in the téléinformatique
+/// standard, the screen is cleared when changed.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Columns {
+    Forty,
+    Eighty,
+}
+
+impl ToTerminal for Columns {
+    fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
+        match self {
+            Columns::Forty => [ESC, CSI, PRIVATE, 0x33, SET_MODE].to_terminal(term),
+            Columns::Eighty => [ESC, CSI, PRIVATE, 0x33, RESET_MODE].to_terminal(term),
+        }
+    }
+}
+
+/// Toggles the display of the keys typed on the keyboard by the terminal
+/// itself, by routing the keyboard to the screen. The terminal answers
+/// with the routing status of the screen.
+///
+/// When the terminal is known to be in the téléinformatique standard, the
+/// echo is toggled with the ANSI send/receive mode instead (`ESC [ 12 l`
+/// and `ESC [ 12 h`) which is not answered.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Echo {
+    Enable,
+    Disable,
+}
+
+impl ToTerminal for Echo {
+    fn to_terminal(&self, term: &mut dyn WriteableTerminal) -> Result<(), Error> {
+        if term.standard() == Some(ParserStandard::Teleinformatique) {
+            let mode = match self {
+                Echo::Enable => RESET_MODE,
+                Echo::Disable => SET_MODE,
+            };
+
+            return term.write(&[ESC, CSI, b'1', b'2', mode]);
+        }
+
+        let routing = match self {
+            Echo::Enable => ROUTING_ON,
+            Echo::Disable => ROUTING_OFF,
+        };
+
+        [
+            ESC,
+            PRO3,
+            routing,
+            Module::Screen.receiver_code(),
+            Module::Keyboard.emitter_code(),
+        ]
+        .to_terminal(term)
+    }
+}
+
 pub trait SpeedAwareTerminal {
     fn match_connector_speed(&mut self) -> Result<(), Error>;
     fn set_connector_speed(&mut self, baud_rate: BaudRate) -> Result<(), Error>;
@@ -44,27 +144,45 @@ pub trait ProtocolExtension: ReadableTerminal + WriteableTerminal {
         self.flush()?;
 
         //p143
-        thread::sleep(Duration::from_millis(500));
+        self.wait(Duration::from_millis(500));
 
-        expect_sequence!(self, [SEP, 0x5E])
+        expect_response!(self, Response::Acknowledgement(0x5E))
     }
 
-    #[cfg(feature = "minitel2")]
     fn sleep(&mut self) -> Result<(), Error> {
+        require(self, Feature::Standby)?;
         self.discard()?;
         self.write(&[ESC, PRO3, START, SCREEN, 0x41])?;
         self.flush()?;
 
-        expect_sequence!(self, [SEP, 0x72])
+        expect_response!(self, Response::Acknowledgement(0x72))
     }
 
-    #[cfg(feature = "minitel2")]
     fn wake(&mut self) -> Result<(), Error> {
+        require(self, Feature::Standby)?;
         self.discard()?;
         self.write(&[ESC, PRO3, STOP, SCREEN, 0x41])?;
         self.flush()?;
 
-        expect_sequence!(self, [SEP, 0x72])
+        expect_response!(self, Response::Acknowledgement(0x72))
+    }
+
+    /// Asks the terminal for its manufacturer, model and software version
+    fn identify(&mut self) -> Result<Identification, Error> {
+        self.discard()?;
+        self.write(&[ESC, PRO1, ENQROM])?;
+        self.flush()?;
+
+        expect_response!(self, Response::Identification(identification) => Ok(identification))
+    }
+
+    /// Identifies the terminal and validates the following sequences
+    /// against the profile of the identified model
+    fn detect_profile(&mut self) -> Result<TerminalProfile, Error> {
+        let profile = self.identify()?.profile();
+        self.set_profile(profile);
+
+        Ok(profile)
     }
 
     fn get_connector_speed(&mut self) -> Result<BaudRate, Error> {
@@ -72,9 +190,126 @@ pub trait ProtocolExtension: ReadableTerminal + WriteableTerminal {
         self.write(&[ESC, PRO1, REQ_SPEED])?;
         self.flush()?;
 
-        expect_sequence!(self, [ESC, PRO2, RESP_SPEED, speed] => {
-            BaudRate::try_from(*speed)
-        })
+        expect_response!(self, Response::Speed(speed) => Ok(speed))
+    }
+
+    /// Asks the terminal for its functioning status, the terminal status
+    /// register (`ESC PRO1 0x70`) is out of scope
+    fn status(&mut self) -> Result<Status, Error> {
+        self.discard()?;
+        self.write(&[ESC, PRO1, REQ_STATUS])?;
+        self.flush()?;
+
+        self.functional_state()
+    }
+
+    /// Reads the functioning status sent back by the terminal after a
+    /// status request or a PRO2 start/stop sequence. The context follows
+    /// the status reported by the terminal.
+    fn functional_state(&mut self) -> Result<Status, Error> {
+        let status = expect_response!(self, Response::Status(status) => Ok(status))?;
+        self.observe(&[ESC, PRO2, STATE_RESPONSE, status.to_byte()]);
+
+        Ok(status)
+    }
+
+    /// Connects or disconnects the emitter of a module to the receiver of
+    /// another one, for instance to stop echoing the keyboard on the screen
+    fn route(&mut self, from: Module, to: Module, on: bool) -> Result<(), Error> {
+        self.discard()?;
+        self.write(&[
+            ESC,
+            PRO3,
+            if on { ROUTING_ON } else { ROUTING_OFF },
+            to.receiver_code(),
+            from.emitter_code(),
+        ])?;
+        self.flush()?;
+
+        let status = read_routing(self, to)?;
+
+        if (status & from.status_bit() != 0) == on {
+            Ok(())
+        } else {
+            Err(Error::UnexpectedResponse(Response::Routing(to, status)))
+        }
+    }
+
+    /// Asks the terminal for the modules emitting to the receiver
+    fn routing(&mut self, to: Module) -> Result<Vec<Module>, Error> {
+        self.discard()?;
+        self.write(&[ESC, PRO2, ROUTING_REQUEST, to.receiver_code()])?;
+        self.flush()?;
+
+        let status = read_routing(self, to)?;
+
+        Ok(Module::ALL
+            .into_iter()
+            .filter(|from| status & from.status_bit() != 0)
+            .collect())
+    }
+
+    /// Asks the terminal for the position of the cursor as `(x, y)`
+    fn cursor_position(&mut self) -> Result<(u8, u8), Error> {
+        self.discard()?;
+        self.write(&[ESC, ENQ_CURSOR])?;
+        self.flush()?;
+
+        expect_response!(self, Response::CursorPosition(y, x) => Ok((x, y)))
+    }
+
+    /// Asks the terminal for the position of the cursor and moves the
+    /// cursor of the context there if they drifted apart. Returns true
+    /// if the context had to be corrected.
+    fn resync_cursor(&mut self) -> Result<bool, Error>
+    where
+        Self: Contextualized,
+    {
+        let (x, y) = self.cursor_position()?;
+
+        if (self.ctx().cursor_x, self.ctx().cursor_y) == (x, y) {
+            return Ok(false);
+        }
+
+        self.observe(&[US, 0x40 + y, 0x40 + x]);
+
+        Ok(true)
+    }
+
+    fn set_mode(&mut self, mode: Mode) -> Result<(), Error> {
+        self.discard()?;
+        self.write(&[
+            ESC,
+            PRO2,
+            MIXED,
+            match mode {
+                Mode::Mixte => TO_MIXED,
+                Mode::Videotex => TO_VIDEOTEX,
+            },
+        ])?;
+        self.flush()?;
+
+        match mode {
+            Mode::Mixte => expect_response!(self, Response::Acknowledgement(MIXED_ACK)),
+            Mode::Videotex => expect_response!(self, Response::Acknowledgement(VIDEOTEX_ACK)),
+        }
+    }
+
+    fn set_standard(&mut self, standard: Standard) -> Result<(), Error> {
+        self.discard()?;
+        self.write(standard.sequence())?;
+        self.flush()?;
+
+        match standard {
+            Standard::Teleinformatique => expect_response!(
+                self,
+                Response::Other(sequence) if sequence == TELEINFORMATIQUE_ACK
+            ),
+            Standard::Teletel => expect_response!(
+                self,
+                Response::Acknowledgement(ack) if [SEP, ack] == TELETEL_ACK
+            ),
+        }
     }
 
     fn set_page_mode(&mut self, mode: PageMode) -> Result<(), Error> {
@@ -88,80 +323,307 @@ pub trait ProtocolExtension: ReadableTerminal + WriteableTerminal {
             SCROLL,
         ])?;
 
-        let mut response = vec![0; 4];
-        self.read_exact(&mut response)?;
+        let status = self.functional_state()?;
+
+        match (mode, status.page_mode) {
+            (PageMode::Page, ParserPageMode::Page) => Ok(()),
+            (PageMode::Scroll, ParserPageMode::Scroll) => Ok(()),
+            _ => Err(Error::UnexpectedResponse(Response::Status(status))),
+        }
+    }
+}
+
+impl<T: ReadableTerminal + WriteableTerminal> ProtocolExtension for T {}
+
+/// Refuses the feature if the profile of the terminal is known
+/// and does not support it
+fn require<T: WriteableTerminal + ?Sized>(term: &T, feature: Feature) -> Result<(), Error> {
+    match term.profile() {
+        Some(profile) if !profile.supports(feature) => Err(Error::Unsupported(feature)),
+        _ => Ok(()),
+    }
+}
+
+/// Reads the routing status of the receiver sent back by the terminal,
+/// the context follows the reported routing
+fn read_routing<T: ReadableTerminal + WriteableTerminal + ?Sized>(
+    term: &mut T,
+    to: Module,
+) -> Result<u8, Error> {
+    let status = expect_response!(term, Response::Routing(module, status) if module == to => {
+        Ok(status)
+    })?;
+
+    term.observe(&[ESC, PRO3, ROUTING_RESPONSE, to.receiver_code(), STATUS_BYTE | status]);
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::{Buffer, Context, Manufacturer, Model, RawBuffer};
+    use teletel_protocol::parser::{Mode as ParserMode, Parser, Standard as ParserStandard};
 
-        if response[0] != ESC || response[1] != PRO2 || response[2] != STATE_RESPONSE {
-            return Err(Error::UnexpectedSequence(response));
+    /// Terminal answering the protocol requests it receives like the emulator
+    struct Loopback {
+        parser: Parser,
+        response: Vec<u8>,
+    }
+
+    impl Loopback {
+        fn new(profile: TerminalProfile) -> Self {
+            Loopback {
+                parser: Parser::from_profile(profile),
+                response: Vec::new(),
+            }
         }
+    }
 
-        let is_scroll_enabled = response[3] & PAGE_MODE;
+    impl ReadableTerminal for Loopback {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            self.response.extend(self.parser.take_response());
 
-        match mode {
-            PageMode::Page if is_scroll_enabled == 0 => Ok(()),
-            PageMode::Scroll if is_scroll_enabled != 0 => Ok(()),
-            _ => Err(Error::UnexpectedSequence(response)),
+            let count = buf.len().min(self.response.len());
+            buf[..count].copy_from_slice(&self.response[..count]);
+            self.response.drain(..count);
+
+            Ok(count)
         }
     }
-}
 
-impl<T: ReadableTerminal + WriteableTerminal> ProtocolExtension for T {}
+    impl WriteableTerminal for Loopback {
+        fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+            buf.iter().try_for_each(|byte| self.parser.consume(*byte).map_err(Into::into))
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn profile(&self) -> Option<TerminalProfile> {
+            Some(*self.parser.profile())
+        }
+
+        fn standard(&self) -> Option<ParserStandard> {
+            Some(self.parser.ctx().standard)
+        }
+
+        fn set_profile(&mut self, profile: TerminalProfile) {
+            self.parser.set_profile(profile);
+        }
+    }
+
+    /// Terminal whose context is kept apart from the terminal answering
+    struct Drifting {
+        term: Loopback,
+        host: Buffer,
+    }
+
+    impl ReadableTerminal for Drifting {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+            self.term.read(buf)
+        }
+    }
+
+    impl WriteableTerminal for Drifting {
+        fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+            self.term.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn observe(&mut self, response: &[u8]) {
+            self.host.observe(response);
+        }
+    }
+
+    impl Contextualized for Drifting {
+        fn ctx(&self) -> &Context {
+            self.host.ctx()
+        }
+    }
+
+    #[test]
+    fn test_mode() {
+        let mut data = RawBuffer::new();
+        Mode::Mixte.to_terminal(&mut data).unwrap();
+        assert_eq!(data.data(), [0x1B, 0x3A, 0x32, 0x7D]);
+
+        let mut data = RawBuffer::new();
+        Mode::Videotex.to_terminal(&mut data).unwrap();
+        assert_eq!(data.data(), [0x1B, 0x3A, 0x32, 0x7E]);
+    }
+
+    #[test]
+    fn test_mode_resizes_context() {
+        let mut buffer = Buffer::new();
+
+        buffer.send(Mode::Mixte).unwrap();
+        assert_eq!(buffer.ctx().mode, ParserMode::Mixte);
+        assert_eq!(buffer.ctx().screen_width, 80);
 
-//uniquement en standard teleinformatique, pas encore assez bien compris et
-//géré par la librairie pour être implémenté, porterait juste à confusion
-//
-// pub enum Echo {
-//     Enable,
-//     Disable,
-// }
-//
-// impl ToTerminal for Echo {
-//     fn to_terminal(&self, term: &mut crate::Minitel) {
-//         match self {
-//             Echo::Enable => term.send(&[ESC, CSI, 0x31, 0x32, 0x6C]),
-//             Echo::Disable => term.send(&[ESC, CSI, 0x31, 0x32, 0x68]),
-//         }
-//     }
-// }
-//
-// pub enum Standard {
-//     Teleinformatique,
-//     Teletel,
-// }
-//
-// impl ToMinitel for Standard {
-//     fn to_terminal(&self, term: &mut crate::Minitel) {
-//         match self {
-//             Standard::Teleinformatique => term.send(&[ESC, 0x3A, 0x31, 0x7D]),
-//             Standard::Teletel => term.send(&[ESC, CSI, 0x3F, 0x7B]),
-//         }
-//     }
-// }
-//
-// pub enum Mode {
-//     Mixte,
-//     Videotex,
-// }
-//
-// impl ToMinitel for Mode {
-//     fn to_terminal(&self, term: &mut crate::Minitel) {
-//         match self {
-//             Mode::Mixte => term.send(&[ESC, 0x3A, 0x32, 0x7D]),
-//             Mode::Videotex => term.send(&[ESC, 0x3A, 0x32, 0x7E]),
-//         }
-//     }
-// }
-//
-// pub enum Columns {
-//     Forty,
-//     Eighty,
-// }
-//
-// impl ToMinitel for Columns {
-//     fn to_terminal(&self, term: &mut crate::Minitel) {
-//         match self {
-//             Columns::Forty => term.send(&[ESC, CSI, 0x3C, 0x33, 0x68]),
-//             Columns::Eighty => term.send(&[ESC, CSI, 0x3F, 0x33, 0x6C]),
-//         }
-//     }
-// }
+        buffer.send(Mode::Videotex).unwrap();
+        assert_eq!(buffer.ctx().mode, ParserMode::Videotex);
+        assert_eq!(buffer.ctx().screen_width, 40);
+    }
+
+    #[test]
+    fn test_standard() {
+        let mut data = RawBuffer::new();
+        Standard::Teleinformatique.to_terminal(&mut data).unwrap();
+        assert_eq!(data.data(), [0x1B, 0x3A, 0x31, 0x7D]);
+
+        let mut data = RawBuffer::new();
+        Standard::Teletel.to_terminal(&mut data).unwrap();
+        assert_eq!(data.data(), [0x1B, 0x5B, 0x3F, 0x7B]);
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut data = RawBuffer::new();
+        Columns::Forty.to_terminal(&mut data).unwrap();
+        assert_eq!(data.data(), [0x1B, 0x5B, 0x3F, 0x33, 0x68]);
+
+        let mut data = RawBuffer::new();
+        Columns::Eighty.to_terminal(&mut data).unwrap();
+        assert_eq!(data.data(), [0x1B, 0x5B, 0x3F, 0x33, 0x6C]);
+    }
+
+    #[test]
+    fn test_echo() {
+        let mut buffer = Buffer::new();
+
+        buffer.send(Echo::Disable).unwrap();
+        assert_eq!(buffer.data(), [0x1B, 0x3B, 0x60, 0x58, 0x51]);
+        assert!(!buffer.ctx().local_echo());
+
+        buffer.send(Echo::Enable).unwrap();
+        assert!(buffer.ctx().local_echo());
+
+        let mut buffer = Buffer::new();
+        buffer.send(Standard::Teleinformatique).unwrap();
+
+        buffer.send(Echo::Disable).unwrap();
+        assert_eq!(buffer.data()[4..], [0x1B, 0x5B, 0x31, 0x32, 0x68]);
+        assert!(!buffer.ctx().local_echo());
+
+        buffer.send(Echo::Enable).unwrap();
+        assert_eq!(buffer.data()[9..], [0x1B, 0x5B, 0x31, 0x32, 0x6C]);
+        assert!(buffer.ctx().local_echo());
+    }
+
+    #[test]
+    fn test_standard_switches_context() {
+        let mut buffer = Buffer::new();
+
+        buffer.send(Standard::Teleinformatique).unwrap();
+        assert_eq!(buffer.ctx().standard, ParserStandard::Teleinformatique);
+        assert_eq!(buffer.ctx().screen_width, 80);
+
+        buffer.send(Columns::Forty).unwrap();
+        assert_eq!(buffer.ctx().screen_width, 40);
+
+        buffer.send(*b"\x1B[2;3H\x1B[31mA").unwrap();
+        assert_eq!(buffer.ctx().grid.cell(3, 2).content, 'A');
+        assert_eq!(buffer.ctx().grid.cell(3, 2).attributes.foreground, RED);
+
+        buffer.send(Standard::Teletel).unwrap();
+        assert_eq!(buffer.ctx().standard, ParserStandard::Teletel);
+        assert_eq!(buffer.ctx().screen_width, 40);
+    }
+
+    #[test]
+    fn test_require_checks_profile() {
+        let minitel_1b = Buffer::with_profile(TerminalProfile::MINITEL_1B);
+        assert!(matches!(
+            require(&minitel_1b, Feature::Standby),
+            Err(Error::Unsupported(Feature::Standby))
+        ));
+
+        assert!(require(&Buffer::new(), Feature::Standby).is_ok());
+        assert!(require(&RawBuffer::new(), Feature::Standby).is_ok());
+    }
+
+    #[test]
+    fn test_status() {
+        let mut term = Loopback::new(TerminalProfile::MINITEL_2);
+
+        Mode::Mixte.to_terminal(&mut term).unwrap();
+        term.parser.take_response();
+
+        let status = term.status().unwrap();
+        assert!(status.eighty_columns);
+        assert_eq!(status.page_mode, ParserPageMode::Page);
+        assert!(!status.lowercase);
+        assert!(!status.pce);
+    }
+
+    #[test]
+    fn test_functional_state_updates_context() {
+        let mut buffer = Buffer::new();
+
+        buffer.observe(&[ESC, PRO2, STATE_RESPONSE, STATUS_BYTE | PAGE_MODE | LOWERCASE]);
+        assert_eq!(buffer.ctx().page_mode, ParserPageMode::Scroll);
+        assert!(buffer.ctx().lowercase);
+    }
+
+    #[test]
+    fn test_route() {
+        let mut term = Loopback::new(TerminalProfile::MINITEL_2);
+
+        term.route(Module::Keyboard, Module::Screen, false).unwrap();
+        assert!(!term.parser.ctx().routing.is_connected(Module::Keyboard, Module::Screen));
+        assert_eq!(term.routing(Module::Screen).unwrap(), [Module::Modem]);
+
+        term.route(Module::Keyboard, Module::Socket, true).unwrap();
+        assert_eq!(term.routing(Module::Socket).unwrap(), [Module::Keyboard, Module::Modem]);
+    }
+
+    #[test]
+    fn test_resync_cursor() {
+        let mut term = Loopback::new(TerminalProfile::MINITEL_2);
+        term.write(&[US, 0x43, 0x45]).unwrap();
+
+        assert_eq!(term.cursor_position().unwrap(), (5, 3));
+
+        //the host context drifts from the terminal
+        let mut host = Buffer::new();
+        host.write(&[US, 0x41, 0x41]).unwrap();
+
+        let mut drifting = Drifting { term, host };
+        assert!(drifting.resync_cursor().unwrap());
+        assert_eq!((drifting.ctx().cursor_x, drifting.ctx().cursor_y), (5, 3));
+        assert!(!drifting.resync_cursor().unwrap());
+    }
+
+    #[test]
+    fn test_identify() {
+        let mut term = Loopback::new(TerminalProfile::MINITEL_1B);
+
+        let identification = term.identify().unwrap();
+        assert_eq!(identification.manufacturer, Manufacturer::TelicAlcatel);
+        assert_eq!(identification.model, Model::Minitel1B);
+
+        term.parser.set_identification(Identification {
+            model: Model::Minitel2,
+            ..identification
+        });
+
+        assert_eq!(term.detect_profile().unwrap(), TerminalProfile::MINITEL_2);
+        assert_eq!(term.profile(), Some(TerminalProfile::MINITEL_2));
+    }
+
+    #[test]
+    fn test_invalid_sequences_before_response() {
+        let mut term = Loopback::new(TerminalProfile::MINITEL_1B);
+
+        //line noise and a routing response from an unknown module
+        term.response.extend([0xFF, ESC, PRO3, ROUTING_RESPONSE, 0x00, STATUS_BYTE]);
+        term.write(&[ESC, PRO1, REQ_STATUS]).unwrap();
+
+        assert!(matches!(term.read_response(), Ok(Response::Status(_))));
+    }
+}